@@ -0,0 +1,390 @@
+//! adapter/file.rs: Storage adapter which keeps the log in a single append-only file
+//!
+//! The file holds every block in the log as a sequence of length-delimited protobufs (the same
+//! format written by `ithos export`). Everything else in the database can be derived from the
+//! log, so it's kept in memory and rebuilt by replaying the log whenever the database is opened.
+//! Blocks are appended to the file, and synced to disk, when the transactions adding them are
+//! committed.
+//!
+//! Unlike the LMDB adapter this needs no C toolchain to build and has no map size to outgrow,
+//! but opening a database takes time proportional to the length of its log.
+//!
+//! A process which crashes while appending can leave a partly written block at the end of the
+//! log. Such a block was never committed, so it's cut off when the database is next opened.
+//!
+//! As each process keeps its own copy of the state in memory, only one process may have a
+//! database open at a time. This is enforced with an exclusive lock (`flock` on Unix) on a file
+//! alongside the log (with a `.lock` suffix), which the operating system releases when the
+//! database is closed or the process holding it exits.
+//!
+
+use adapter::{Adapter, Table, Transaction, WriteTransaction};
+use adapter::memory::{self, MemoryAdapter};
+use block::Block;
+use errors::*;
+use protobuf::{self, CodedOutputStream};
+use std::fs::{File, OpenOptions, TryLockError};
+use std::io::{self, Read};
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path as StdPath, PathBuf as StdPathBuf};
+use transform::Transform;
+
+#[cfg(unix)]
+const FILE_PERMS: u32 = 0o600;
+
+/// Adapter implementation which stores the log in a single append-only file
+pub struct FileAdapter {
+    /// Directory state, rebuilt from the log when the database is opened
+    state: MemoryAdapter,
+
+    /// Log file which committed blocks are appended to
+    file: File,
+
+    /// Lock preventing other processes from opening the database
+    _lock: Lock,
+}
+
+impl<'a> Adapter<'a> for FileAdapter {
+    type R = memory::RoTransaction;
+    type W = RwTransaction<'a>;
+
+    fn create_database(path: &StdPath) -> Result<FileAdapter> {
+        let lock = Lock::acquire(path)?;
+
+        let mut options = OpenOptions::new();
+        options.read(true).append(true).create_new(true);
+
+        #[cfg(unix)]
+        options.mode(FILE_PERMS);
+
+        let file = options.open(path).map_err(
+            |err| if err.kind() == io::ErrorKind::AlreadyExists {
+                Error::from(ErrorKind::EntryAlreadyExists(path.display().to_string()))
+            } else {
+                err.into()
+            },
+        )?;
+
+        Ok(FileAdapter {
+            state: MemoryAdapter::new(),
+            file: file,
+            _lock: lock,
+        })
+    }
+
+    fn open_database(path: &StdPath) -> Result<FileAdapter> {
+        let lock = Lock::acquire(path)?;
+        let mut file = OpenOptions::new().read(true).append(true).open(path)?;
+        let state = MemoryAdapter::new();
+
+        replay(&state, &mut file).chain_err(|| {
+            format!("couldn't replay the log in {}", path.display())
+        })?;
+
+        Ok(FileAdapter {
+            state: state,
+            file: file,
+            _lock: lock,
+        })
+    }
+
+    fn ro_transaction(&'a self) -> Result<memory::RoTransaction> {
+        self.state.ro_transaction()
+    }
+
+    fn rw_transaction(&'a self) -> Result<RwTransaction<'a>> {
+        Ok(RwTransaction {
+            txn: self.state.rw_transaction()?,
+            file: &self.file,
+            blocks: Vec::new(),
+        })
+    }
+}
+
+/// Exclusive lock on the lock file for a log, held until dropped.
+///
+/// The lock file itself is left in place: removing it could let another process lock a file
+/// which is about to be replaced, and a lock file which exists but isn't locked is harmless.
+struct Lock {
+    /// Open lock file, which the lock is released along with when closed
+    _file: File,
+}
+
+impl Lock {
+    /// Lock the lock file for the log at the given path, creating it if needed. Fails with
+    /// `DatabaseLocked` if another open database holds the lock
+    fn acquire(log_path: &StdPath) -> Result<Lock> {
+        let mut path = log_path.as_os_str().to_owned();
+        path.push(".lock");
+        let path = StdPathBuf::from(path);
+
+        let mut options = OpenOptions::new();
+        options.write(true).create(true);
+
+        #[cfg(unix)]
+        options.mode(FILE_PERMS);
+
+        let file = options.open(&path)?;
+
+        match file.try_lock() {
+            Ok(()) => Ok(Lock { _file: file }),
+            Err(TryLockError::WouldBlock) => {
+                let msg = format!("{} is open in another process", log_path.display());
+                Err(ErrorKind::DatabaseLocked(msg).into())
+            }
+            Err(TryLockError::Error(err)) => Err(err.into()),
+        }
+    }
+}
+
+/// Rebuild the state of the directory by replaying every block in the log.
+///
+/// If the last block in the log is incomplete, or can't be parsed or applied, it's assumed to
+/// have been torn by a crash partway through appending it, and the log is truncated to the end
+/// of the block before it. Any other block which fails is an error.
+fn replay(state: &MemoryAdapter, file: &mut File) -> Result<()> {
+    let mut log = Vec::new();
+    file.read_to_end(&mut log)?;
+
+    let mut offset = 0;
+
+    while offset < log.len() {
+        let (start, end) = match record_bounds(&log[offset..]) {
+            Some((start, end)) if offset + end <= log.len() => (offset + start, offset + end),
+            _ => return truncate(file, offset),
+        };
+
+        if let Err(err) = replay_block(state, &log[start..end]) {
+            if end < log.len() {
+                return Err(err);
+            }
+
+            return truncate(file, offset);
+        }
+
+        offset = end;
+    }
+
+    Ok(())
+}
+
+/// Parse a serialized block and apply it to the state
+fn replay_block(state: &MemoryAdapter, bytes: &[u8]) -> Result<()> {
+    let block: Block = protobuf::parse_from_bytes(bytes)?;
+
+    let mut transform = Transform::new(state)?;
+    transform.apply(&block)?;
+    transform.commit()
+}
+
+/// Find where the block in the length-delimited record at the start of the given bytes begins
+/// and ends, unless its length prefix is itself incomplete
+fn record_bounds(bytes: &[u8]) -> Option<(usize, usize)> {
+    let mut length = 0usize;
+
+    // Lengths are written as varints of at most 5 bytes (see `append`)
+    for (i, byte) in bytes.iter().take(5).enumerate() {
+        length |= ((byte & 0x7f) as usize) << (7 * i);
+
+        if byte & 0x80 == 0 {
+            return Some((i + 1, i + 1 + length));
+        }
+    }
+
+    None
+}
+
+/// Cut the log short at the given offset, discarding everything after it
+fn truncate(file: &File, offset: usize) -> Result<()> {
+    file.set_len(offset as u64)?;
+    Ok(file.sync_data()?)
+}
+
+/// Append the given serialized blocks to the log as length-delimited protobufs, then sync it
+fn append(mut file: &File, blocks: &[Vec<u8>]) -> Result<()> {
+    {
+        let mut output = CodedOutputStream::new(&mut file);
+
+        for block in blocks {
+            output.write_raw_varint32(block.len() as u32)?;
+            output.write_raw_bytes(block)?;
+        }
+
+        output.flush()?;
+    }
+
+    Ok(file.sync_data()?)
+}
+
+/// Read-write transaction: only one allowed at a time
+pub struct RwTransaction<'a> {
+    /// Transaction on the in-memory state
+    txn: memory::RwTransaction<'a>,
+
+    /// Log file to append to on commit
+    file: &'a File,
+
+    /// Serialized blocks added in this transaction
+    blocks: Vec<Vec<u8>>,
+}
+
+impl<'a> Transaction for RwTransaction<'a> {
+    fn commit(self) -> Result<()> {
+        let RwTransaction { txn, file, blocks } = self;
+
+        if !blocks.is_empty() {
+            // Don't leave part of a block behind if the log can't be appended to
+            let length = file.metadata()?.len();
+
+            if let Err(err) = append(file, &blocks) {
+                file.set_len(length)?;
+                return Err(err);
+            }
+        }
+
+        txn.commit()
+    }
+
+    fn abort(self) {
+        self.txn.abort()
+    }
+
+    fn get(&self, table: Table, key: &[u8]) -> Result<&[u8]> {
+        self.txn.get(table, key)
+    }
+
+    fn get_all(&self, table: Table, key: &[u8]) -> Result<Vec<&[u8]>> {
+        self.txn.get_all(table, key)
+    }
+
+    fn last(&self, table: Table) -> Result<Option<(&[u8], &[u8])>> {
+        self.txn.last(table)
+    }
+
+    fn pairs(&self, table: Table) -> Result<Vec<(&[u8], &[u8])>> {
+        self.txn.pairs(table)
+    }
+}
+
+impl<'a> WriteTransaction for RwTransaction<'a> {
+    fn put(&mut self, table: Table, key: &[u8], value: &[u8]) -> Result<()> {
+        if table == Table::Blocks {
+            self.blocks.push(value.to_vec());
+        }
+
+        self.txn.put(table, key, value)
+    }
+
+    fn delete(&mut self, table: Table, key: &[u8], value: Option<&[u8]>) -> Result<()> {
+        if table == Table::Blocks {
+            let msg = "blocks can't be removed from an append-only log".to_string();
+            return Err(ErrorKind::StructureInvalid(msg).into());
+        }
+
+        self.txn.delete(table, key, value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use adapter::{Adapter, Transaction};
+    use adapter::file::FileAdapter;
+    use adapter::tests::example_block;
+    use errors::*;
+    use id::BlockId;
+    use std::fs::{self, File, OpenOptions};
+    use std::io::Write;
+    use tempdir::TempDir;
+
+    fn create_database() -> FileAdapter {
+        let dir = TempDir::new("ithos-test").unwrap();
+        FileAdapter::create_database(&dir.path().join("ithos.log")).unwrap()
+    }
+
+    adapter_tests!();
+
+    #[test]
+    fn reopen() {
+        let dir = TempDir::new("ithos-test").unwrap();
+        let path = dir.path().join("ithos.log");
+        let block = example_block();
+
+        {
+            let adapter = FileAdapter::create_database(&path).unwrap();
+            let mut txn = adapter.rw_transaction().unwrap();
+            adapter.add_block(&mut txn, &block).unwrap();
+            txn.commit().unwrap();
+        }
+
+        // Opening the database replays the log
+        let adapter = FileAdapter::open_database(&path).unwrap();
+        let txn = adapter.ro_transaction().unwrap();
+        assert_eq!(adapter.current_block_id(&txn).unwrap(), BlockId::of(&block));
+
+        assert!(FileAdapter::create_database(&path).is_err());
+    }
+
+    #[test]
+    fn exclusive_lock() {
+        let dir = TempDir::new("ithos-test").unwrap();
+        let path = dir.path().join("ithos.log");
+
+        {
+            let _adapter = FileAdapter::create_database(&path).unwrap();
+
+            match FileAdapter::open_database(&path) {
+                Err(Error(ErrorKind::DatabaseLocked(_), _)) => (),
+                Err(other) => panic!("unexpected error: {:?}", other),
+                Ok(_) => panic!("expected a second open to be rejected"),
+            }
+        }
+
+        // The lock is released when the database is closed
+        let _adapter = FileAdapter::open_database(&path).unwrap();
+
+        match FileAdapter::open_database(&path) {
+            Err(Error(ErrorKind::DatabaseLocked(_), _)) => (),
+            Err(other) => panic!("unexpected error: {:?}", other),
+            Ok(_) => panic!("expected a second open to be rejected"),
+        }
+    }
+
+    #[test]
+    fn stale_lock_file() {
+        let dir = TempDir::new("ithos-test").unwrap();
+        let path = dir.path().join("ithos.log");
+
+        // A lock file left behind by a process which exited without unlocking it is reused
+        File::create(dir.path().join("ithos.log.lock")).unwrap();
+        FileAdapter::create_database(&path).unwrap();
+    }
+
+    #[test]
+    fn truncated_tail() {
+        let dir = TempDir::new("ithos-test").unwrap();
+        let path = dir.path().join("ithos.log");
+        let block = example_block();
+
+        {
+            let adapter = FileAdapter::create_database(&path).unwrap();
+            let mut txn = adapter.rw_transaction().unwrap();
+            adapter.add_block(&mut txn, &block).unwrap();
+            txn.commit().unwrap();
+        }
+
+        let length = fs::metadata(&path).unwrap().len();
+
+        // Simulate a crash partway through appending a 128 byte block
+        {
+            let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+            file.write_all(&[0x80, 0x01, 0x0a, 0x20]).unwrap();
+        }
+
+        // The torn block is cut off, leaving the last complete block in place
+        let adapter = FileAdapter::open_database(&path).unwrap();
+        let txn = adapter.ro_transaction().unwrap();
+        assert_eq!(adapter.current_block_id(&txn).unwrap(), BlockId::of(&block));
+        assert_eq!(fs::metadata(&path).unwrap().len(), length);
+    }
+}
@@ -1,605 +1,669 @@
 //! adapter/lmdb.rs: Storage adapter for the Lightning Memory-Mapped Database (LMDB)
 //!
-//! This adapter is presently the one-and-only storage adapter. Much of the logic it contains
-//! should probably be extracted elsewhere before a second storage adapter is written, as a lot of
-//! it is reusable and the adapter abstraction is not fully fleshed out.
+//! Each table is stored in its own LMDB "database". Tables with integer keys use `INTEGER_KEY`
+//! so they're ordered numerically, and multi-valued tables use `DUP_SORT`.
+//!
+//! The environment is configured by an optional `lmdb.toml` file in the database's directory
+//! (see `LmdbConfig`), which is read whenever the database is created or opened:
+//!
+//! ```toml
+//! map_size = 1073741824
+//! max_readers = 126
+//! no_sync = false
+//! no_meta_sync = true
+//! read_only = false
+//! auto_grow = true
+//! ```
+//!
+//! LMDB databases can't outgrow their memory map, so unless `auto_grow` is disabled the map is
+//! doubled before beginning a write transaction whenever it's more than half full, or after a
+//! write has failed because it was full.
 //!
 
 extern crate lmdb;
 extern crate lmdb_sys;
 
-use self::lmdb::{Environment, Database, DatabaseFlags, Cursor, WriteFlags, DUP_SORT, INTEGER_KEY};
+use self::lmdb::{Environment, EnvironmentFlags, Database, DatabaseFlags, Cursor, WriteFlags,
+                 DUP_SORT, INTEGER_KEY, NO_META_SYNC, NO_SYNC, READ_ONLY};
 pub use self::lmdb::Error as LmdbError;
 use self::lmdb::Transaction as LmdbTransaction;
-use adapter::{Adapter, Transaction};
-use block::Block;
-use direntry::DirEntry;
-use entry::{self, SerializedEntry};
+use adapter::{Adapter, Table, Transaction, WriteTransaction, TABLES};
 use errors::*;
-use id::{BlockId, EntryId};
-use metadata::Metadata;
-use path::Path;
-use protobuf::{self, Message};
-use std::io::Write;
+use id::ENTRY_ID_SIZE;
+use std::cmp;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::mem;
+use std::os::raw::c_int;
 use std::path::Path as StdPath;
-use std::str;
+use std::sync::{RwLock, RwLockReadGuard};
+use std::sync::atomic::{AtomicBool, Ordering};
+use toml;
 
-const MAX_DBS: u32 = 8;
+const MAX_DBS: u32 = 16;
 const DB_PERMS: lmdb_sys::mode_t = 0o600;
 
-// Names of "databases" within LMDB: effectively namespaces for keys
-const BLOCKS_DB: &str = "blocks";
-const DIRECTORIES_DB: &str = "directories";
-const ENTRIES_DB: &str = "entries";
-const METADATA_DB: &str = "metadata";
-const STATE_DB: &str = "state";
+/// Name of the (optional) configuration file within a database's directory
+pub const CONFIG_FILE: &str = "lmdb.toml";
 
-// Names of keys within the "state" database
-const LOG_ID_KEY: &[u8] = b"log_id";
-const LATEST_BLOCK_ID_KEY: &[u8] = b"latest_block_id";
+/// Initial size of the memory map unless otherwise configured (LMDB's default is only 10 MiB)
+pub const DEFAULT_MAP_SIZE: usize = 64 * 1024 * 1024;
 
-/// Adapter implementation for the Lightning Memory Database
-pub struct LmdbAdapter {
-    /// LMDB "environment" (directory containing multiple databases)
-    env: Environment,
+/// Maximum number of concurrent read-only transactions unless otherwise configured
+pub const DEFAULT_MAX_READERS: u32 = 126;
 
-    /// Blocks in the log, persisted by ID
-    blocks: Database,
+/// Configuration for the LMDB environment a database is stored in
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LmdbConfig {
+    /// Size of the memory map in bytes, i.e. the maximum size of the database (unless it's
+    /// grown automatically)
+    pub map_size: usize,
 
-    /// Directory hierarchy, mapping names to entry IDs
-    directories: Database,
+    /// Maximum number of concurrent read-only transactions
+    pub max_readers: u32,
 
-    /// Entries indexed by integer entry ID
-    entries: Database,
+    /// Don't flush to disk when committing: much faster, but a system crash may lose the most
+    /// recent commits or even corrupt the database
+    pub no_sync: bool,
 
-    /// Per-entry metadata, e.g. creation/modification time
-    metadata: Database,
+    /// Flush data, but not metadata, to disk when committing: a system crash may lose the most
+    /// recent commit, but can't corrupt the database
+    pub no_meta_sync: bool,
 
-    /// Global metadata about the current state of the directory
-    state: Database,
+    /// Open the database read-only (write transactions will fail)
+    pub read_only: bool,
+
+    /// Grow the memory map automatically as the database fills it
+    pub auto_grow: bool,
 }
 
-impl<'a> Adapter<'a> for LmdbAdapter {
-    type R = RoTransaction<'a>;
-    type W = RwTransaction<'a>;
+impl Default for LmdbConfig {
+    fn default() -> LmdbConfig {
+        LmdbConfig {
+            map_size: DEFAULT_MAP_SIZE,
+            max_readers: DEFAULT_MAX_READERS,
+            no_sync: false,
+            no_meta_sync: false,
+            read_only: false,
+            auto_grow: true,
+        }
+    }
+}
 
-    fn create_database(path: &StdPath) -> Result<LmdbAdapter> {
-        let env = Environment::new()
-            .set_max_dbs(MAX_DBS)
-            .open_with_permissions(path, DB_PERMS)?;
+impl LmdbConfig {
+    /// Load the configuration file in the given database directory, or the default
+    /// configuration if there isn't one
+    pub fn load(path: &StdPath) -> Result<LmdbConfig> {
+        let config_path = path.join(CONFIG_FILE);
 
-        let blocks = env.create_db(Some(BLOCKS_DB), DatabaseFlags::empty())?;
-        let directories = env.create_db(Some(DIRECTORIES_DB), INTEGER_KEY | DUP_SORT)?;
-        let entries = env.create_db(Some(ENTRIES_DB), INTEGER_KEY)?;
-        let metadata = env.create_db(Some(METADATA_DB), INTEGER_KEY)?;
-        let state = env.create_db(Some(STATE_DB), DatabaseFlags::empty())?;
+        if !config_path.is_file() {
+            return Ok(LmdbConfig::default());
+        }
 
-        Ok(LmdbAdapter {
-            env: env,
-            blocks: blocks,
-            directories: directories,
-            entries: entries,
-            metadata: metadata,
-            state: state,
-        })
-    }
+        let mut contents = String::new();
+        File::open(&config_path)?.read_to_string(&mut contents)?;
 
-    fn open_database(path: &StdPath) -> Result<LmdbAdapter> {
-        let env = Environment::new()
-            .set_max_dbs(MAX_DBS)
-            .open_with_permissions(path, DB_PERMS)?;
+        toml::from_str(&contents).map_err(|err| ErrorKind::ParseFailure(err.to_string()).into())
+    }
 
-        let blocks = env.open_db(Some(BLOCKS_DB))?;
-        let directories = env.open_db(Some(DIRECTORIES_DB))?;
-        let entries = env.open_db(Some(ENTRIES_DB))?;
-        let metadata = env.open_db(Some(METADATA_DB))?;
-        let state = env.open_db(Some(STATE_DB))?;
+    /// Save this configuration to the configuration file in the given database directory
+    pub fn save(&self, path: &StdPath) -> Result<()> {
+        let contents = toml::to_string(self).map_err(|err| {
+            Error::from(ErrorKind::SerializationFailure(err.to_string()))
+        })?;
 
-        Ok(LmdbAdapter {
-            env: env,
-            blocks: blocks,
-            directories: directories,
-            entries: entries,
-            metadata: metadata,
-            state: state,
-        })
+        File::create(path.join(CONFIG_FILE))?.write_all(contents.as_bytes())?;
+        Ok(())
     }
 
-    fn ro_transaction(&'a self) -> Result<RoTransaction<'a>> {
-        Ok(RoTransaction(self.env.begin_ro_txn()?))
-    }
+    /// Open (or create) an LMDB environment with this configuration
+    fn open_environment(&self, path: &StdPath) -> Result<Environment> {
+        let mut flags = EnvironmentFlags::empty();
 
-    fn rw_transaction(&'a self) -> Result<RwTransaction<'a>> {
-        Ok(RwTransaction(self.env.begin_rw_txn()?))
-    }
+        if self.no_sync {
+            flags.insert(NO_SYNC);
+        }
 
-    fn next_free_entry_id(&self, txn: &RwTransaction) -> Result<EntryId> {
-        let cursor = txn.0.open_ro_cursor(self.entries)?;
+        if self.no_meta_sync {
+            flags.insert(NO_META_SYNC);
+        }
 
-        let last_id = match cursor.get(None, None, lmdb_sys::MDB_LAST) {
-            Ok((id, _)) => EntryId::from_bytes(id.unwrap()).unwrap(),
-            Err(_) => EntryId::root(),
-        };
+        if self.read_only {
+            flags.insert(READ_ONLY);
+        }
 
-        Ok(last_id.next())
+        Ok(Environment::new()
+            .set_flags(flags)
+            .set_max_dbs(MAX_DBS)
+            .set_max_readers(self.max_readers)
+            .set_map_size(self.map_size)
+            .open_with_permissions(path, DB_PERMS)?)
     }
+}
 
-    fn add_block<'t>(&'t self, txn: &'t mut RwTransaction, block: &Block) -> Result<()> {
-        let block_id = BlockId::of(block);
-        let parent_id = &block.get_body().parent_id;
+/// Adapter implementation for the Lightning Memory Database
+pub struct LmdbAdapter {
+    /// LMDB "environment" (directory containing multiple databases)
+    env: Environment,
 
-        // Ensure the block we're adding is the next in the chain
-        if *parent_id == BlockId::zero().as_ref() {
-            match txn.lmdb_get(self.state, LOG_ID_KEY) {
-                Ok(_) => {
-                    let msg = "initial block already set".to_string();
-                    return Err(ErrorKind::EntryAlreadyExists(msg).into());
-                }
-                Err(Error(ErrorKind::Lmdb(LmdbError::NotFound), _)) => (),
-                Err(err) => return Err(err),
-            };
-
-            txn.put(self.state, LOG_ID_KEY, block_id.as_ref())?;
-        } else if *parent_id != self.current_block_id(txn)?.as_ref() {
-            let msg = "new block's parent does not match current ID".to_string();
-            return Err(ErrorKind::OrderingInvalid(msg).into());
-        }
+    /// LMDB "databases" for each table, in the same order as `TABLES`
+    databases: Vec<Database>,
 
-        // This check should be redundant given the one above, but is here just in case
-        match txn.lmdb_get(self.blocks, block_id.as_ref()) {
-            Ok(_) => {
-                let msg = "new block has already been committed".to_string();
-                return Err(ErrorKind::EntryAlreadyExists(msg).into());
-            }
-            Err(Error(ErrorKind::Lmdb(LmdbError::NotFound), _)) => (),
-            Err(err) => return Err(err),
-        }
+    /// Configuration the environment was opened with
+    config: LmdbConfig,
 
-        // Store the new block
-        txn.put(
-            self.blocks,
-            block_id.as_ref(),
-            &block.write_to_bytes()?,
-        )?;
+    /// Held shared by every transaction in progress, and exclusively while the map is resized
+    /// (which LMDB only allows when no transactions are in progress)
+    map_lock: RwLock<()>,
 
-        // Update the current block ID in the state table
-        txn.put(self.state, LATEST_BLOCK_ID_KEY, block_id.as_ref())?;
+    /// Set when a write fails because the map is full, so it's grown before the next one
+    map_full: AtomicBool,
+}
 
-        Ok(())
+impl LmdbAdapter {
+    /// Create a new database at the given path, with the given configuration
+    pub fn create_with_config(path: &StdPath, config: LmdbConfig) -> Result<LmdbAdapter> {
+        let env = config.open_environment(path)?;
+
+        let databases = TABLES
+            .iter()
+            .map(|table| env.create_db(Some(table.name()), database_flags(*table)))
+            .collect::<::std::result::Result<Vec<Database>, LmdbError>>()?;
+
+        Ok(LmdbAdapter::new(env, databases, config))
     }
 
-    fn current_block_id<'t, T>(&'t self, txn: &'t T) -> Result<BlockId>
-    where
-        T: Transaction,
-    {
-        BlockId::from_bytes(txn.lmdb_get(self.state, LATEST_BLOCK_ID_KEY)?)
-    }
-
-    fn add_entry<'t>(
-        &'t self,
-        txn: &'t mut RwTransaction,
-        entry: &SerializedEntry,
-        name: &'t str,
-        parent_id: EntryId,
-        metadata: &Metadata,
-    ) -> Result<DirEntry> {
-        match txn.lmdb_get(self.entries, entry.id.as_ref()) {
-            Ok(_) => {
-                let msg = format!(
-                    "error creating '{}': entry ID '{:?}' already exists",
-                    name,
-                    entry.id
-                );
-                return Err(ErrorKind::EntryAlreadyExists(msg).into());
-            }
-            Err(Error(ErrorKind::Lmdb(LmdbError::NotFound), _)) => (),
-            Err(err) => return Err(err).chain_err(|| format!("error creating {}", name)),
-        }
+    /// Open an existing database at the given path, with the given configuration
+    pub fn open_with_config(path: &StdPath, config: LmdbConfig) -> Result<LmdbAdapter> {
+        let env = config.open_environment(path)?;
 
-        match txn.lmdb_get(self.directories, parent_id.as_ref()) {
-            Ok(_) => {
-                match self.find_child(txn, parent_id, name) {
-                    Ok(_) => {
-                        let msg = format!("entry '{}' already exists", name);
-                        return Err(ErrorKind::EntryAlreadyExists(msg).into());
-                    }
-                    Err(Error(ErrorKind::NotFound(_), _)) => (),
-                    Err(err) => {
-                        return Err(err).chain_err(|| {
-                            format!("error finding child of entry ID {:?}", parent_id)
-                        })
-                    }
-                }
+        let mut missing = Vec::new();
+
+        for table in &TABLES {
+            match env.open_db(Some(table.name())) {
+                Ok(_) => (),
+                Err(LmdbError::NotFound) => missing.push(*table),
+                Err(err) => return Err(err.into()),
             }
-            Err(Error(ErrorKind::Lmdb(LmdbError::NotFound), _)) => (),
-            Err(err) => return Err(err).chain_err(|| format!("error creating {}", name)),
         }
 
-        let direntry = DirEntry {
-            id: entry.id,
-            parent_id: parent_id,
-            name: name,
-        };
+        // Tables added since the database was created are missing. The children table can be
+        // rebuilt from the directories table, but the others could only be rebuilt by replaying
+        // the log, and logs written before they existed can't be replayed by this version (they
+        // have no root policy, and ops and objects have since gained fields which change their
+        // digests). Check for them before changing anything
+        if missing.iter().any(|table| *table != Table::Children) {
+            let names: Vec<&str> = missing.iter().map(|table| table.name()).collect();
+            let msg = format!(
+                "{} has no {} tables: it was created by an older version of ithos which is \
+                 incompatible with this one, and must be recreated",
+                path.display(),
+                names.join(", ")
+            );
+            return Err(ErrorKind::UpgradeRequired(msg).into());
+        }
 
-        if entry.id != EntryId::root() {
-            txn.put(
-                self.directories,
-                parent_id.as_ref(),
-                &direntry.to_bytes(),
-            )?;
+        if !missing.is_empty() && config.read_only {
+            let msg = format!(
+                "{} has no children table: open it once without read_only to add it",
+                path.display()
+            );
+            return Err(ErrorKind::UpgradeRequired(msg).into());
         }
 
-        txn.put(
-            self.metadata,
-            entry.id.as_ref(),
-            &metadata.write_to_bytes()?,
-        )?;
+        let databases = TABLES
+            .iter()
+            .map(|table| if missing.contains(table) {
+                env.create_db(Some(table.name()), database_flags(*table))
+            } else {
+                env.open_db(Some(table.name()))
+            })
+            .collect::<::std::result::Result<Vec<Database>, LmdbError>>()?;
 
-        let mut buffer = txn.reserve(
-            self.entries,
-            entry.id.as_ref(),
-            entry::HEADER_SIZE + entry.data.len(),
-        )?;
+        let adapter = LmdbAdapter::new(env, databases, config);
 
-        buffer.write_all(&entry.class.as_bytes())?;
-        buffer.write_all(entry.data)?;
+        if !missing.is_empty() {
+            adapter.index_children()?;
+        }
 
-        Ok(direntry)
+        Ok(adapter)
     }
 
-    fn find_direntry<'t, T>(&'t self, txn: &'t T, path: &Path) -> Result<DirEntry>
-    where
-        T: Transaction,
-    {
-        let result = path.components().iter().fold(
-            Ok(DirEntry::root()),
-            |parent_direntry, component| {
-                self.find_child(txn, parent_direntry?.id, component)
-            },
-        );
+    /// Configuration the database was opened with
+    pub fn config(&self) -> &LmdbConfig {
+        &self.config
+    }
 
-        match result {
-            Ok(_) => result,
-            Err(Error(ErrorKind::Lmdb(LmdbError::NotFound), _)) => {
-                result.chain_err(|| format!("not found: {:?}", path))
-            }
-            Err(_) => result.chain_err(|| format!("error reading {:?}", path)),
+    fn new(env: Environment, databases: Vec<Database>, config: LmdbConfig) -> LmdbAdapter {
+        LmdbAdapter {
+            env: env,
+            databases: databases,
+            config: config,
+            map_lock: RwLock::new(()),
+            map_full: AtomicBool::new(false),
         }
     }
 
-    fn find_metadata<'t, T>(&'t self, txn: &'t T, id: &EntryId) -> Result<Metadata>
-    where
-        T: Transaction,
-    {
-        let proto = txn.lmdb_get(self.metadata, id.as_ref())?;
-        Ok(protobuf::parse_from_bytes::<Metadata>(proto)?)
+    /// Populate the children table from the directories table (for databases created before
+    /// the children table was added)
+    fn index_children(&self) -> Result<()> {
+        let mut txn = self.rw_transaction()?;
+
+        let children: Vec<(Vec<u8>, Vec<u8>)> = txn.pairs(Table::Directories)?
+            .iter()
+            .map(|&(parent_id, direntry_bytes)| {
+                let mut key = parent_id.to_vec();
+                key.extend_from_slice(&direntry_bytes[ENTRY_ID_SIZE..]);
+                (key, direntry_bytes.to_vec())
+            })
+            .collect();
+
+        for (key, direntry_bytes) in children {
+            txn.put(Table::Children, &key, &direntry_bytes)?;
+        }
+
+        txn.commit()
     }
 
-    fn find_entry<'t, T>(&'t self, txn: &'t T, id: &EntryId) -> Result<SerializedEntry>
-    where
-        T: Transaction,
-    {
-        let bytes = txn.lmdb_get(self.entries, id.as_ref())?;
-        SerializedEntry::from_bytes(*id, bytes)
+    /// Take note of writes which failed because the map is full
+    fn check_full<T>(&self, result: ::std::result::Result<T, LmdbError>) -> Result<T> {
+        if let Err(LmdbError::MapFull) = result {
+            self.map_full.store(true, Ordering::SeqCst);
+        }
+
+        Ok(result?)
+    }
+
+    /// Double the size of the map if it's more than half full, or a write has failed because
+    /// it was full. NOTE: `map_lock` must be held exclusively
+    #[allow(unsafe_code)]
+    fn grow_map(&self) -> Result<()> {
+        let env = self.env.env();
+
+        // Safe as the environment is open, and the structs are only written by LMDB
+        let (map_size, used) = unsafe {
+            let mut info: lmdb_sys::MDB_envinfo = mem::zeroed();
+            let mut stat: lmdb_sys::MDB_stat = mem::zeroed();
+            lmdb_result(lmdb_sys::mdb_env_info(env, &mut info))?;
+            lmdb_result(lmdb_sys::mdb_env_stat(env, &mut stat))?;
+
+            (info.me_mapsize, (info.me_last_pgno + 1) * stat.ms_psize as usize)
+        };
+
+        if !self.map_full.swap(false, Ordering::SeqCst) && used <= map_size / 2 {
+            return Ok(());
+        }
+
+        // Safe as the caller guarantees there are no transactions in progress
+        unsafe { lmdb_result(lmdb_sys::mdb_env_set_mapsize(env, cmp::max(map_size, used) * 2)) }
     }
 }
 
-impl LmdbAdapter {
-    fn find_child<'a, T>(&'a self, txn: &'a T, parent_id: EntryId, name: &str) -> Result<DirEntry>
-    where
-        T: Transaction,
-    {
-        let direntry_bytes = txn.lmdb_find(
-            self.directories,
-            parent_id.as_ref(),
-            |direntry_bytes| {
-                let direntry = DirEntry::new(parent_id, direntry_bytes).unwrap();
-                direntry.name == name
-            },
-        )?;
-
-        DirEntry::new(parent_id, direntry_bytes)
+/// Convert a return code from the LMDB C API into a result
+fn lmdb_result(code: c_int) -> Result<()> {
+    if code == lmdb_sys::MDB_SUCCESS {
+        Ok(())
+    } else {
+        Err(LmdbError::from_err_code(code).into())
     }
 }
 
-/// Internal functionality which is not part of the public `Transaction` API
-/// NOTE: these methods should not be called from outside of this module
-pub trait AdapterTransaction {
-    /// Underlying transaction type from the `lmdb` crate
-    type T: LmdbTransaction;
+impl<'a> Adapter<'a> for LmdbAdapter {
+    type R = RoTransaction<'a>;
+    type W = RwTransaction<'a>;
 
-    /// Obtain the inner `LmdbTransaction`
-    fn lmdb_txn(&self) -> &Self::T;
+    fn create_database(path: &StdPath) -> Result<LmdbAdapter> {
+        LmdbAdapter::create_with_config(path, LmdbConfig::load(path)?)
+    }
 
-    /// Get the raw data associated with an object (TODO: remove this from this trait)
-    fn lmdb_get(&self, db: Database, key: &[u8]) -> Result<&[u8]> {
-        Ok(self.lmdb_txn().get(db, &key)?)
+    fn open_database(path: &StdPath) -> Result<LmdbAdapter> {
+        LmdbAdapter::open_with_config(path, LmdbConfig::load(path)?)
     }
 
-    /// Perform a search of the given database, looking for an entry that matches the predicate
-    // TODO: since LMDB is ordered, we could e.g. perform a binary search
-    fn lmdb_find<P>(&self, db: Database, key: &[u8], predicate: P) -> Result<&[u8]>
-    where
-        P: Fn(&[u8]) -> bool,
-    {
-        // Ensure the entry exists
-        // TODO: Fix upstream unwrap in lmdb crate's iter_from
-        self.lmdb_get(db, key)?;
+    fn ro_transaction(&'a self) -> Result<RoTransaction<'a>> {
+        let map_lock = self.map_lock.read().expect("LMDB map lock poisoned");
 
-        let mut cursor = self.lmdb_txn().open_ro_cursor(db)?;
-        let mut result = None;
+        Ok(LmdbTxn {
+            txn: self.env.begin_ro_txn()?,
+            adapter: self,
+            _map_lock: map_lock,
+        })
+    }
 
-        // TODO: Remove earlier check once this no longer panics on missing keys
-        for (cursor_key, value) in cursor.iter_from(key) {
-            if cursor_key != key {
-                return Err(ErrorKind::NotFound("key not found".to_string()).into());
-            }
+    fn rw_transaction(&'a self) -> Result<RwTransaction<'a>> {
+        if self.config.read_only {
+            let msg = format!("read_only is set in {}", CONFIG_FILE);
+            return Err(ErrorKind::ReadOnly(msg).into());
+        }
 
-            if predicate(value) {
-                result = Some(value);
-                break;
+        // The map can't be resized if this thread (or any other) has transactions in progress,
+        // in which case it'll be grown before a later write transaction instead
+        if self.config.auto_grow {
+            if let Ok(_resizing) = self.map_lock.try_write() {
+                self.grow_map()?;
             }
         }
 
-        result.ok_or_else(|| ErrorKind::NotFound("key not found".to_string()).into())
+        let map_lock = self.map_lock.read().expect("LMDB map lock poisoned");
+
+        Ok(LmdbTxn {
+            txn: self.env.begin_rw_txn()?,
+            adapter: self,
+            _map_lock: map_lock,
+        })
+    }
+}
+
+/// Flags for the LMDB database storing the given table
+fn database_flags(table: Table) -> DatabaseFlags {
+    let mut flags = DatabaseFlags::empty();
+
+    if table.has_integer_keys() {
+        flags.insert(INTEGER_KEY);
     }
+
+    if table.is_multi_valued() {
+        flags.insert(DUP_SORT);
+    }
+
+    flags
 }
 
 /// Read-write transaction: only one allowed at a time
-pub struct RwTransaction<'a>(self::lmdb::RwTransaction<'a>);
+pub type RwTransaction<'a> = LmdbTxn<'a, self::lmdb::RwTransaction<'a>>;
 
 /// Read-only transaction: several can be active concurrently
-pub struct RoTransaction<'a>(self::lmdb::RoTransaction<'a>);
+pub type RoTransaction<'a> = LmdbTxn<'a, self::lmdb::RoTransaction<'a>>;
 
-impl<'a> AdapterTransaction for RwTransaction<'a> {
-    type T = self::lmdb::RwTransaction<'a>;
+/// Transaction from the `lmdb` crate, along with the adapter it belongs to
+pub struct LmdbTxn<'a, T> {
+    txn: T,
+    adapter: &'a LmdbAdapter,
+    _map_lock: RwLockReadGuard<'a, ()>,
+}
 
-    fn lmdb_txn(&self) -> &self::lmdb::RwTransaction<'a> {
-        &self.0
+impl<'a, T> LmdbTxn<'a, T> {
+    /// Obtain the LMDB database storing the given table
+    fn db(&self, table: Table) -> Database {
+        self.adapter.databases[table as usize]
     }
 }
 
-impl<'a> AdapterTransaction for RoTransaction<'a> {
-    type T = self::lmdb::RoTransaction<'a>;
+impl<'a, T: LmdbTransaction> Transaction for LmdbTxn<'a, T> {
+    fn commit(self) -> Result<()> {
+        let adapter = self.adapter;
+        adapter.check_full(self.txn.commit())
+    }
 
-    fn lmdb_txn(&self) -> &self::lmdb::RoTransaction<'a> {
-        &self.0
+    fn abort(self) {
+        self.txn.abort()
     }
-}
 
-impl<'a> Transaction for RwTransaction<'a> {
-    fn commit(self) -> Result<()> {
-        Ok(self.0.commit()?)
+    fn get(&self, table: Table, key: &[u8]) -> Result<&[u8]> {
+        match self.txn.get(self.db(table), &key) {
+            Ok(value) => Ok(value),
+            Err(LmdbError::NotFound) => {
+                let msg = format!("key not found in {}", table.name());
+                Err(ErrorKind::NotFound(msg).into())
+            }
+            Err(err) => Err(err.into()),
+        }
     }
-}
 
-impl<'a> Transaction for RoTransaction<'a> {
-    fn commit(self) -> Result<()> {
-        Ok(self.0.commit()?)
+    fn get_all(&self, table: Table, key: &[u8]) -> Result<Vec<&[u8]>> {
+        // Ensure the entry exists
+        // TODO: Fix upstream unwrap in lmdb crate's iter_from
+        match self.get(table, key) {
+            Ok(_) => (),
+            Err(Error(ErrorKind::NotFound(_), _)) => return Ok(Vec::new()),
+            Err(err) => return Err(err),
+        }
+
+        let mut cursor = self.txn.open_ro_cursor(self.db(table))?;
+        let mut result = Vec::new();
+
+        for (cursor_key, value) in cursor.iter_from(key) {
+            if cursor_key != key {
+                break;
+            }
+
+            result.push(value);
+        }
+
+        Ok(result)
     }
-}
 
-impl<'a> RwTransaction<'a> {
-    /// Reserve the given amount of space in LMDB for the given key
-    pub fn reserve(&mut self, database: Database, key: &[u8], len: usize) -> Result<&mut [u8]> {
-        Ok(self.0.reserve(database, &key, len, WriteFlags::empty())?)
+    fn last(&self, table: Table) -> Result<Option<(&[u8], &[u8])>> {
+        let cursor = self.txn.open_ro_cursor(self.db(table))?;
 
+        match cursor.get(None, None, lmdb_sys::MDB_LAST) {
+            Ok((Some(key), value)) => Ok(Some((key, value))),
+            Ok((None, _)) |
+            Err(LmdbError::NotFound) => Ok(None),
+            Err(err) => Err(err.into()),
+        }
     }
 
-    /// Put the given data into LMDB under the given key
-    fn put(&mut self, database: Database, key: &[u8], data: &[u8]) -> Result<()> {
-        Ok(self.0.put(database, &key, &data, WriteFlags::empty())?)
+    fn pairs(&self, table: Table) -> Result<Vec<(&[u8], &[u8])>> {
+        let mut cursor = self.txn.open_ro_cursor(self.db(table))?;
+
+        // NOTE: `iter` (unlike `iter_start`) doesn't panic on empty databases
+        Ok(cursor.iter().collect())
+    }
+}
+
+impl<'a> WriteTransaction for RwTransaction<'a> {
+    fn put(&mut self, table: Table, key: &[u8], value: &[u8]) -> Result<()> {
+        let db = self.db(table);
+        let result = self.txn.put(db, &key, &value, WriteFlags::empty());
+        self.adapter.check_full(result)
+    }
+
+    fn delete(&mut self, table: Table, key: &[u8], value: Option<&[u8]>) -> Result<()> {
+        let db = self.db(table);
+        Ok(self.txn.del(db, &key, value)?)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use adapter::{Adapter, Transaction};
-    use adapter::lmdb::LmdbAdapter;
-    use alg::CipherSuite;
-    use block::Block;
-    use crypto::signing::KeyPair;
-    use entry::{Class, SerializedEntry};
+    use adapter::{Adapter, Table, Transaction, WriteTransaction};
+    use adapter::lmdb::{LmdbAdapter, LmdbConfig, LmdbError};
+    use adapter::lmdb::lmdb::{DatabaseFlags, Environment};
     use errors::*;
-    use id::{BlockId, EntryId};
-    use metadata::Metadata;
-    use path::Path;
-    use ring::rand;
-    use setup;
     use tempdir::TempDir;
-    use timestamp::Timestamp;
 
     fn create_database() -> LmdbAdapter {
         let dir = TempDir::new("ithos-test").unwrap();
         LmdbAdapter::create_database(dir.path()).unwrap()
     }
 
-    fn example_timestamp() -> Timestamp {
-        Timestamp::at(1_231_006_505)
-    }
+    adapter_tests!();
+
+    /// Write the given number of 16 KiB values in separate transactions
+    fn fill(adapter: &LmdbAdapter, count: usize) -> Result<()> {
+        let value = vec![0u8; 16 * 1024];
 
-    fn example_block() -> Block {
-        let rng = rand::SystemRandom::new();
-        let admin_keypair = KeyPair::generate(&rng);
+        for i in 0..count {
+            let mut txn = adapter.rw_transaction()?;
+            txn.put(Table::State, format!("key{}", i).as_bytes(), &value)?;
+            txn.commit()?;
+        }
 
-        setup::create_log(
-            CipherSuite::Ed25519_AES256GCM_SHA256,
-            setup::tests::ADMIN_USERNAME,
-            &admin_keypair,
-            setup::tests::ADMIN_KEYPAIR_SEALED,
-            setup::tests::ADMIN_KEYPAIR_SALT,
-            setup::tests::COMMENT,
-        )
+        Ok(())
     }
 
-    fn example_metadata() -> Metadata {
-        let mut metadata = Metadata::new();
+    fn small_map_config() -> LmdbConfig {
+        LmdbConfig {
+            map_size: 128 * 1024,
+            ..LmdbConfig::default()
+        }
+    }
 
-        let block_id = BlockId::zero();
+    #[test]
+    fn auto_grow() {
+        let dir = TempDir::new("ithos-test").unwrap();
+        let adapter = LmdbAdapter::create_with_config(dir.path(), small_map_config()).unwrap();
 
-        metadata.set_created_id(Vec::from(block_id.as_ref()));
-        metadata.set_updated_id(Vec::from(block_id.as_ref()));
-        metadata.set_created_at(example_timestamp().to_int());
-        metadata.set_updated_at(example_timestamp().to_int());
+        fill(&adapter, 64).unwrap();
 
-        metadata
+        let txn = adapter.ro_transaction().unwrap();
+        assert_eq!(txn.pairs(Table::State).unwrap().len(), 64);
     }
 
-    fn example_entry(id: EntryId, data: &[u8]) -> SerializedEntry {
-        SerializedEntry {
-            id: id,
-            class: Class::Root,
-            data: data,
+    #[test]
+    fn map_full() {
+        let dir = TempDir::new("ithos-test").unwrap();
+        let config = LmdbConfig {
+            auto_grow: false,
+            ..small_map_config()
+        };
+
+        let adapter = LmdbAdapter::create_with_config(dir.path(), config).unwrap();
+
+        match *fill(&adapter, 64).unwrap_err().kind() {
+            ErrorKind::Lmdb(LmdbError::MapFull) => (),
+            ref other => panic!("unexpected error kind: {:?}", other),
         }
     }
 
     #[test]
-    fn duplicate_block() {
-        let adapter = create_database();
-        let block = example_block();
+    fn config_file() {
+        let dir = TempDir::new("ithos-test").unwrap();
+        assert_eq!(LmdbConfig::load(dir.path()).unwrap(), LmdbConfig::default());
 
-        let mut txn = adapter.rw_transaction().unwrap();
-        adapter.add_block(&mut txn, &block).unwrap();
-        txn.commit().unwrap();
+        let config = LmdbConfig {
+            no_meta_sync: true,
+            ..small_map_config()
+        };
 
-        let mut txn = adapter.rw_transaction().unwrap();
-        let err = adapter.add_block(&mut txn, &block).expect_err(
-            "expected duplicate block to cause error",
-        );
+        config.save(dir.path()).unwrap();
+        assert_eq!(LmdbConfig::load(dir.path()).unwrap(), config);
 
-        match *err.kind() {
-            ErrorKind::EntryAlreadyExists(ref msg) => assert_eq!(msg, "initial block already set"),
-            ref other => panic!("unexpected error kind: {:?}", other),
-        }
+        // The configuration file is used when the database is created
+        let adapter = LmdbAdapter::create_database(dir.path()).unwrap();
+        assert_eq!(adapter.config(), &config);
     }
 
     #[test]
-    fn entry_lookup() {
-        let adapter = create_database();
-        let example_data = b"just an example host entry";
-
-        {
-            let mut txn = adapter.rw_transaction().unwrap();
+    fn read_only() {
+        let dir = TempDir::new("ithos-test").unwrap();
+        LmdbAdapter::create_database(dir.path()).unwrap();
 
-            let domain_id = adapter.next_free_entry_id(&txn).unwrap();
-            adapter
-                .add_entry(
-                    &mut txn,
-                    &example_entry(domain_id, b"example domain entry"),
-                    "example.com",
-                    EntryId::root(),
-                    &example_metadata(),
-                )
-                .unwrap();
-
-            let hosts_id = domain_id.next();
-            adapter
-                .add_entry(
-                    &mut txn,
-                    &example_entry(hosts_id, b"example hosts ou"),
-                    "hosts",
-                    domain_id,
-                    &example_metadata(),
-                )
-                .unwrap();
-
-            let host_id = hosts_id.next();
-            adapter
-                .add_entry(
-                    &mut txn,
-                    &example_entry(host_id, example_data),
-                    "master.example.com",
-                    hosts_id,
-                    &example_metadata(),
-                )
-                .unwrap();
+        let config = LmdbConfig {
+            read_only: true,
+            ..LmdbConfig::default()
+        };
 
-            txn.commit().unwrap();
-        }
+        let adapter = LmdbAdapter::open_with_config(dir.path(), config).unwrap();
+        assert!(adapter.ro_transaction().is_ok());
 
-        {
-            let txn = adapter.ro_transaction().unwrap();
+        let result = adapter.rw_transaction();
 
-            {
-                let path = Path::new("/example.com/hosts/master.example.com").unwrap();
+        match result {
+            Err(Error(ErrorKind::ReadOnly(_), _)) => (),
+            Err(other) => panic!("unexpected error: {:?}", other),
+            Ok(_) => panic!("expected write transaction to be rejected"),
+        }
+    }
 
-                let direntry = adapter.find_direntry(&txn, path).unwrap();
-                assert_eq!(direntry.name, "master.example.com");
+    #[test]
+    fn missing_tables() {
+        let dir = TempDir::new("ithos-test").unwrap();
 
-                let metadata = adapter.find_metadata(&txn, &direntry.id).unwrap();
-                assert_eq!(metadata.created_at, example_timestamp().to_int());
+        // Tables present in databases created by the earliest versions of ithos
+        {
+            let env = Environment::new().set_max_dbs(16).open(dir.path()).unwrap();
 
-                let entry = adapter.find_entry(&txn, &direntry.id).unwrap();
-                assert_eq!(entry.data, &example_data[..]);
+            for name in &["blocks", "directories", "entries", "metadata", "state"] {
+                env.create_db(Some(name), DatabaseFlags::empty()).unwrap();
             }
+        }
 
-            txn.commit().unwrap();
+        // The database is left untouched, so it's rejected the same way every time
+        for _ in 0..2 {
+            match LmdbAdapter::open_database(dir.path()) {
+                Err(Error(ErrorKind::UpgradeRequired(ref msg), _)) => {
+                    assert!(msg.contains("heights"))
+                }
+                Err(other) => panic!("unexpected error: {:?}", other),
+                Ok(_) => panic!("expected missing tables to be rejected"),
+            }
         }
     }
+}
 
-    #[test]
-    fn duplicate_entry_id() {
-        let adapter = create_database();
+#[cfg(all(test, feature = "bench"))]
+mod benches {
+    use adapter::{Adapter, Transaction};
+    use adapter::lmdb::LmdbAdapter;
+    use adapter::tests::{example_entry, example_metadata};
+    use id::EntryId;
+    use path::Path;
+    use tempdir::TempDir;
+    use test::Bencher;
 
-        let mut txn = adapter.rw_transaction().unwrap();
+    /// Resolve the path to the last of the given number of entries in the same directory
+    fn find_direntry(b: &mut Bencher, siblings: usize) {
+        let dir = TempDir::new("ithos-bench").unwrap();
+        let adapter = LmdbAdapter::create_database(dir.path()).unwrap();
+        let parent_id = EntryId::root().next();
 
-        let domain_id = adapter.next_free_entry_id(&txn).unwrap();
+        let mut txn = adapter.rw_transaction().unwrap();
         adapter
             .add_entry(
                 &mut txn,
-                &example_entry(domain_id, b"domain"),
-                "example.com",
+                &example_entry(parent_id, b"users"),
+                "users",
                 EntryId::root(),
                 &example_metadata(),
             )
             .unwrap();
+        txn.commit().unwrap();
 
-        let err = adapter
-            .add_entry(
-                &mut txn,
-                &example_entry(domain_id, b"domain"),
-                "another.com",
-                EntryId::root(),
-                &example_metadata(),
-            )
-            .expect_err("expected duplicate entry ID to cause error");
+        let names: Vec<String> = (0..siblings).map(|i| format!("user{}", i)).collect();
+        let mut id = parent_id;
 
-        match *err.kind() {
-            ErrorKind::EntryAlreadyExists(_) => (),
-            ref other => panic!("unexpected error kind: {:?}", other),
+        // Commit in batches so the map can grow between them
+        for batch in names.chunks(1000) {
+            let mut txn = adapter.rw_transaction().unwrap();
+
+            for name in batch {
+                id = id.next();
+                adapter
+                    .add_entry(
+                        &mut txn,
+                        &example_entry(id, b"user"),
+                        name,
+                        parent_id,
+                        &example_metadata(),
+                    )
+                    .unwrap();
+            }
+
+            txn.commit().unwrap();
         }
-    }
 
-    #[test]
-    fn duplicate_entry_name() {
-        let adapter = create_database();
+        let path = format!("/users/user{}", siblings - 1);
+        let path = Path::new(&path).unwrap();
+        let txn = adapter.ro_transaction().unwrap();
 
-        let mut txn = adapter.rw_transaction().unwrap();
+        b.iter(|| adapter.find_direntry(&txn, path).unwrap().id);
+    }
 
-        let domain_id = adapter.next_free_entry_id(&txn).unwrap();
-        adapter
-            .add_entry(
-                &mut txn,
-                &example_entry(domain_id, b"domain"),
-                "example.com",
-                EntryId::root(),
-                &example_metadata(),
-            )
-            .unwrap();
+    #[bench]
+    fn find_direntry_among_10(b: &mut Bencher) {
+        find_direntry(b, 10);
+    }
 
-        let err = adapter
-            .add_entry(
-                &mut txn,
-                &example_entry(domain_id.next(), b"domain"),
-                "example.com",
-                EntryId::root(),
-                &example_metadata(),
-            )
-            .expect_err("expected duplicate entry name to cause error");
+    #[bench]
+    fn find_direntry_among_1000(b: &mut Bencher) {
+        find_direntry(b, 1000);
+    }
 
-        match *err.kind() {
-            ErrorKind::EntryAlreadyExists(_) => (),
-            ref other => panic!("unexpected error kind: {:?}", other),
-        }
+    #[bench]
+    fn find_direntry_among_100000(b: &mut Bencher) {
+        find_direntry(b, 100_000);
     }
 }
@@ -0,0 +1,337 @@
+//! adapter/memory.rs: In-memory storage adapter
+//!
+//! Tables are ordered maps, shared copy-on-write between transactions: read-only transactions
+//! see a snapshot of the tables as of when they began, and read-write transactions keep their
+//! writes to themselves until they're committed. Nothing is persisted, so this adapter is useful
+//! for tests and for scratch state (e.g. replaying a log to verify it).
+//!
+
+use adapter::{Adapter, Table, Transaction, WriteTransaction, TABLES};
+use byteorder::{ByteOrder, NativeEndian};
+use errors::*;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+use std::collections::btree_map::Entry as MapEntry;
+use std::path::Path as StdPath;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Contents of a table: every key is mapped to its values (exactly one in tables which aren't
+/// multi-valued). Within a transaction's uncommitted writes, keys with no values are deleted
+type Contents = BTreeMap<Key, BTreeSet<Vec<u8>>>;
+
+/// Keys within a table, ordered the same way LMDB orders them
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Key {
+    bytes: Vec<u8>,
+    integer: bool,
+}
+
+impl Key {
+    fn new(table: Table, bytes: &[u8]) -> Key {
+        Key {
+            bytes: bytes.to_vec(),
+            integer: table.has_integer_keys() && bytes.len() == 8,
+        }
+    }
+}
+
+impl Ord for Key {
+    fn cmp(&self, other: &Key) -> Ordering {
+        if self.integer && other.integer {
+            NativeEndian::read_u64(&self.bytes).cmp(&NativeEndian::read_u64(&other.bytes))
+        } else {
+            self.bytes.cmp(&other.bytes)
+        }
+    }
+}
+
+impl PartialOrd for Key {
+    fn partial_cmp(&self, other: &Key) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Adapter implementation which keeps everything in memory
+pub struct MemoryAdapter {
+    /// Committed contents of each table, in the same order as `TABLES`
+    tables: Mutex<Arc<Vec<Contents>>>,
+
+    /// Held by the read-write transaction in progress (only one is allowed at a time)
+    writer: Mutex<()>,
+}
+
+impl MemoryAdapter {
+    /// Create a new, empty in-memory database
+    pub fn new() -> MemoryAdapter {
+        MemoryAdapter {
+            tables: Mutex::new(Arc::new(TABLES.iter().map(|_| Contents::new()).collect())),
+            writer: Mutex::new(()),
+        }
+    }
+
+    /// Obtain the committed contents of the tables
+    fn committed(&self) -> Arc<Vec<Contents>> {
+        self.tables.lock().expect("memory adapter lock poisoned").clone()
+    }
+}
+
+impl Default for MemoryAdapter {
+    fn default() -> MemoryAdapter {
+        MemoryAdapter::new()
+    }
+}
+
+impl<'a> Adapter<'a> for MemoryAdapter {
+    type R = RoTransaction;
+    type W = RwTransaction<'a>;
+
+    fn create_database(_path: &StdPath) -> Result<MemoryAdapter> {
+        Ok(MemoryAdapter::new())
+    }
+
+    fn open_database(path: &StdPath) -> Result<MemoryAdapter> {
+        let msg = format!("{}: in-memory databases can't be reopened", path.display());
+        Err(ErrorKind::NotFound(msg).into())
+    }
+
+    fn ro_transaction(&'a self) -> Result<RoTransaction> {
+        Ok(RoTransaction(Snapshot::new(self.committed())))
+    }
+
+    fn rw_transaction(&'a self) -> Result<RwTransaction<'a>> {
+        let writer = self.writer.lock().expect("memory adapter lock poisoned");
+
+        Ok(RwTransaction {
+            adapter: self,
+            writer: writer,
+            snapshot: Snapshot::new(self.committed()),
+        })
+    }
+}
+
+/// Committed tables as of when a transaction began, along with its uncommitted writes
+struct Snapshot {
+    base: Arc<Vec<Contents>>,
+    changes: Vec<Contents>,
+}
+
+impl Snapshot {
+    fn new(base: Arc<Vec<Contents>>) -> Snapshot {
+        Snapshot {
+            base: base,
+            changes: TABLES.iter().map(|_| Contents::new()).collect(),
+        }
+    }
+
+    /// Values stored under the given key (none if it's missing)
+    fn values(&self, table: Table, key: &[u8]) -> Option<&BTreeSet<Vec<u8>>> {
+        let key = Key::new(table, key);
+
+        match self.changes[table as usize].get(&key) {
+            Some(values) => Some(values),
+            None => self.base[table as usize].get(&key),
+        }
+    }
+
+    /// Values stored under the given key, copied into the uncommitted writes so they can be
+    /// modified
+    fn values_mut(&mut self, table: Table, key: &[u8]) -> &mut BTreeSet<Vec<u8>> {
+        let base = &self.base[table as usize];
+
+        match self.changes[table as usize].entry(Key::new(table, key)) {
+            MapEntry::Occupied(entry) => entry.into_mut(),
+            MapEntry::Vacant(entry) => {
+                let values = base.get(entry.key()).cloned().unwrap_or_default();
+                entry.insert(values)
+            }
+        }
+    }
+
+    fn get(&self, table: Table, key: &[u8]) -> Result<&[u8]> {
+        match self.values(table, key).and_then(|values| values.iter().next()) {
+            Some(value) => Ok(&value[..]),
+            None => Err(not_found(table)),
+        }
+    }
+
+    fn get_all(&self, table: Table, key: &[u8]) -> Result<Vec<&[u8]>> {
+        Ok(match self.values(table, key) {
+            Some(values) => values.iter().map(|value| &value[..]).collect(),
+            None => Vec::new(),
+        })
+    }
+
+    fn last(&self, table: Table) -> Result<Option<(&[u8], &[u8])>> {
+        let changes = &self.changes[table as usize];
+
+        let committed = self.base[table as usize].iter().rev().find(
+            |&(key, _)| !changes.contains_key(key),
+        );
+
+        let uncommitted = changes.iter().rev().find(|&(_, values)| !values.is_empty());
+
+        let last = match (committed, uncommitted) {
+            (Some(committed), Some(uncommitted)) => {
+                if uncommitted.0 > committed.0 {
+                    Some(uncommitted)
+                } else {
+                    Some(committed)
+                }
+            }
+            (committed, uncommitted) => committed.or(uncommitted),
+        };
+
+        Ok(last.and_then(|(key, values)| {
+            values.iter().next_back().map(|value| (&key.bytes[..], &value[..]))
+        }))
+    }
+
+    fn pairs(&self, table: Table) -> Result<Vec<(&[u8], &[u8])>> {
+        let mut merged: BTreeMap<&Key, &BTreeSet<Vec<u8>>> =
+            self.base[table as usize].iter().collect();
+
+        merged.extend(self.changes[table as usize].iter());
+
+        Ok(
+            merged
+                .into_iter()
+                .flat_map(|(key, values)| {
+                    values.iter().map(move |value| (&key.bytes[..], &value[..]))
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Error for keys which aren't present in the given table
+fn not_found(table: Table) -> Error {
+    ErrorKind::NotFound(format!("key not found in {}", table.name())).into()
+}
+
+/// Read-only transaction: several can be active concurrently
+pub struct RoTransaction(Snapshot);
+
+/// Read-write transaction: only one allowed at a time
+pub struct RwTransaction<'a> {
+    adapter: &'a MemoryAdapter,
+    writer: MutexGuard<'a, ()>,
+    snapshot: Snapshot,
+}
+
+impl Transaction for RoTransaction {
+    fn commit(self) -> Result<()> {
+        Ok(())
+    }
+
+    fn abort(self) {}
+
+    fn get(&self, table: Table, key: &[u8]) -> Result<&[u8]> {
+        self.0.get(table, key)
+    }
+
+    fn get_all(&self, table: Table, key: &[u8]) -> Result<Vec<&[u8]>> {
+        self.0.get_all(table, key)
+    }
+
+    fn last(&self, table: Table) -> Result<Option<(&[u8], &[u8])>> {
+        self.0.last(table)
+    }
+
+    fn pairs(&self, table: Table) -> Result<Vec<(&[u8], &[u8])>> {
+        self.0.pairs(table)
+    }
+}
+
+impl<'a> Transaction for RwTransaction<'a> {
+    fn commit(self) -> Result<()> {
+        let RwTransaction {
+            adapter,
+            writer,
+            snapshot,
+        } = self;
+
+        // Release our reference to the committed tables first, so they're only copied if
+        // there are read-only transactions still using them
+        let Snapshot { base, changes } = snapshot;
+        drop(base);
+
+        {
+            let mut committed = adapter.tables.lock().expect("memory adapter lock poisoned");
+            let tables = Arc::make_mut(&mut *committed);
+
+            for (contents, changes) in tables.iter_mut().zip(changes) {
+                for (key, values) in changes {
+                    if values.is_empty() {
+                        contents.remove(&key);
+                    } else {
+                        contents.insert(key, values);
+                    }
+                }
+            }
+        }
+
+        drop(writer);
+        Ok(())
+    }
+
+    fn abort(self) {}
+
+    fn get(&self, table: Table, key: &[u8]) -> Result<&[u8]> {
+        self.snapshot.get(table, key)
+    }
+
+    fn get_all(&self, table: Table, key: &[u8]) -> Result<Vec<&[u8]>> {
+        self.snapshot.get_all(table, key)
+    }
+
+    fn last(&self, table: Table) -> Result<Option<(&[u8], &[u8])>> {
+        self.snapshot.last(table)
+    }
+
+    fn pairs(&self, table: Table) -> Result<Vec<(&[u8], &[u8])>> {
+        self.snapshot.pairs(table)
+    }
+}
+
+impl<'a> WriteTransaction for RwTransaction<'a> {
+    fn put(&mut self, table: Table, key: &[u8], value: &[u8]) -> Result<()> {
+        let values = self.snapshot.values_mut(table, key);
+
+        if !table.is_multi_valued() {
+            values.clear();
+        }
+
+        values.insert(value.to_vec());
+        Ok(())
+    }
+
+    fn delete(&mut self, table: Table, key: &[u8], value: Option<&[u8]>) -> Result<()> {
+        let values = self.snapshot.values_mut(table, key);
+
+        let found = match value {
+            Some(value) => values.remove(value),
+            None => {
+                let found = !values.is_empty();
+                values.clear();
+                found
+            }
+        };
+
+        if found {
+            Ok(())
+        } else {
+            Err(not_found(table))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use adapter::memory::MemoryAdapter;
+
+    fn create_database() -> MemoryAdapter {
+        MemoryAdapter::new()
+    }
+
+    adapter_tests!();
+}
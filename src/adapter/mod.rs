@@ -1,45 +1,175 @@
-//! adapter/mod.rs: A not-fully-fleshed-out "aspirational" abstraction for multiple storage adapters
+//! adapter/mod.rs: Abstraction for multiple storage adapters
 //!
 //! This API is heavily inspired by LMDB's multi-reader, single-writer model with separate
-//! read-only and read-write transactions, however it encapsulates LMDB internals and avoids
-//! exposing any LMDB-related types except as associated types (i.e. the database type).
-//!
-//! That said, this is probably not close to the ideal API for an abstract storage adapter, and
-//! much logic that's in lmdb.rs right now should probably get hoisted out first.
+//! read-only and read-write transactions. Adapters only provide transactional access to a
+//! handful of ordered key-value tables (see `Table`), and everything else (the log, the directory
+//! hierarchy, and the indexes used to authorize blocks) is implemented once on top of those
+//! tables by the provided methods of the `Adapter` trait.
 //!
 
-#[cfg(feature = "lmdb")]
-pub mod lmdb;
+#[cfg(test)]
+#[macro_use]
+pub mod tests;
 
-// Allow adapters to define a trait of their internal transaction methods
+#[cfg(feature = "file-adapter")]
+pub mod file;
 
 #[cfg(feature = "lmdb")]
-pub use self::lmdb::AdapterTransaction;
+pub mod lmdb;
+
+#[cfg(feature = "memory-adapter")]
+pub mod memory;
 
 use block::Block;
+use byteorder::{ByteOrder, NativeEndian};
 use direntry::DirEntry;
-use entry::SerializedEntry;
+use entry::{self, Class, Entry, SerializedEntry};
 use errors::*;
-use id::{BlockId, EntryId};
+use id::{BlockId, EntryId, ENTRY_ID_SIZE};
 use metadata::Metadata;
+use object::credential;
 use path;
+use protobuf::{self, Message};
 use std::marker::Sized;
+use std::ops::Range;
 use std::path::Path as StdPath;
+use std::str;
+use timestamp::Timestamp;
+
+// Names of keys within the "state" table
+const LOG_ID_KEY: &[u8] = b"log_id";
+const LATEST_BLOCK_ID_KEY: &[u8] = b"latest_block_id";
+
+/// Every table, in the order they're compared
+pub const TABLES: [Table; 11] = [
+    Table::Blocks,
+    Table::Children,
+    Table::Directories,
+    Table::Entries,
+    Table::Heights,
+    Table::Metadata,
+    Table::Parents,
+    Table::Policies,
+    Table::Signers,
+    Table::State,
+    Table::Witnesses,
+];
+
+/// Tables within a database: effectively namespaces for keys
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum Table {
+    /// Blocks in the log, persisted by ID
+    Blocks,
+
+    /// Directory hierarchy indexed by name, mapping parent IDs and names to child entries
+    Children,
+
+    /// Directory hierarchy, mapping names to entry IDs
+    Directories,
+
+    /// Entries indexed by integer entry ID
+    Entries,
+
+    /// Block IDs indexed by their height (i.e. position in the log)
+    Heights,
+
+    /// Per-entry metadata, e.g. creation/modification time
+    Metadata,
+
+    /// Reverse mapping of entry IDs to their parent IDs and names
+    Parents,
+
+    /// Policies indexed by the ID of the entry whose subtree they apply to
+    Policies,
+
+    /// Public keys of signature key pair credentials, mapped to their entry IDs
+    Signers,
+
+    /// Global metadata about the current state of the directory
+    State,
+
+    /// Paths of the credentials which signed each block as of when it was applied, indexed by
+    /// block ID and public key
+    Witnesses,
+}
+
+impl Table {
+    /// Name of this table
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Table::Blocks => "blocks",
+            Table::Children => "children",
+            Table::Directories => "directories",
+            Table::Entries => "entries",
+            Table::Heights => "heights",
+            Table::Metadata => "metadata",
+            Table::Parents => "parents",
+            Table::Policies => "policies",
+            Table::Signers => "signers",
+            Table::State => "state",
+            Table::Witnesses => "witnesses",
+        }
+    }
+
+    /// Are this table's keys host-native 64-bit integers (which are ordered numerically)?
+    pub fn has_integer_keys(&self) -> bool {
+        match *self {
+            Table::Directories | Table::Entries | Table::Heights | Table::Metadata |
+            Table::Parents | Table::Policies => true,
+            Table::Blocks | Table::Children | Table::Signers | Table::State |
+            Table::Witnesses => false,
+        }
+    }
+
+    /// Can a key in this table hold several values (which are kept in byte order)?
+    pub fn is_multi_valued(&self) -> bool {
+        match *self {
+            Table::Directories | Table::Policies => true,
+            _ => false,
+        }
+    }
+}
 
 /// All access to the underlying storage system is transactional
-pub trait Transaction: AdapterTransaction {
+pub trait Transaction {
     /// Commit this transaction (performing writes if this is a read-write transaction
     fn commit(self) -> Result<()>;
+
+    /// Abort this transaction, discarding any writes made in it
+    fn abort(self);
+
+    /// Get the value stored under the given key (the first one, in multi-valued tables).
+    /// Missing keys are `NotFound` errors
+    fn get(&self, table: Table, key: &[u8]) -> Result<&[u8]>;
+
+    /// Get all of the values stored under the given key, in order (none if it's missing)
+    fn get_all(&self, table: Table, key: &[u8]) -> Result<Vec<&[u8]>>;
+
+    /// Get the greatest key in the given table along with its (last) value, if any
+    fn last(&self, table: Table) -> Result<Option<(&[u8], &[u8])>>;
+
+    /// Get every key/value pair in the given table, in order
+    fn pairs(&self, table: Table) -> Result<Vec<(&[u8], &[u8])>>;
 }
 
-/// Abstract (but still roughly LMDB-shaped) adapter interface with high-level APIs to work with
-/// the various types of data in an ithos directory
+/// Read-write transactions can also modify tables
+pub trait WriteTransaction: Transaction {
+    /// Put the given value under the given key, replacing the existing value (or in multi-valued
+    /// tables, adding to the existing values)
+    fn put(&mut self, table: Table, key: &[u8], value: &[u8]) -> Result<()>;
+
+    /// Delete the given key, or in multi-valued tables, only the given value
+    fn delete(&mut self, table: Table, key: &[u8], value: Option<&[u8]>) -> Result<()>;
+}
+
+/// Adapter interface with high-level APIs to work with the various types of data in an ithos
+/// directory. Adapters implement the transactions, and the rest is provided
 pub trait Adapter<'a> {
     /// Read-only transaction type
     type R: Transaction;
 
     /// Read-write transaction type
-    type W: Transaction;
+    type W: WriteTransaction;
 
     /// Create a new database at the given path
     fn create_database(path: &StdPath) -> Result<Self>
@@ -58,15 +188,135 @@ pub trait Adapter<'a> {
     fn rw_transaction(&'a self) -> Result<Self::W>;
 
     /// Obtain the next available entry ID
-    fn next_free_entry_id(&self, txn: &Self::W) -> Result<EntryId>;
+    fn next_free_entry_id(&self, txn: &Self::W) -> Result<EntryId> {
+        // Metadata outlives deleted entries, so IDs are never reused
+        let last_id = match txn.last(Table::Metadata)? {
+            Some((id, _)) => EntryId::from_bytes(id)?,
+            None => EntryId::root(),
+        };
+
+        Ok(last_id.next())
+    }
 
     /// Add a block to the database (NOTE: just stores the block, doesn't validate/process it)
-    fn add_block<'t>(&'t self, txn: &'t mut Self::W, block: &Block) -> Result<()>;
+    fn add_block<'t>(&'t self, txn: &'t mut Self::W, block: &Block) -> Result<()> {
+        let block_id = BlockId::of(block);
+        let parent_id = &block.get_body().parent_id;
+
+        let height;
+
+        // Ensure the block we're adding is the next in the chain
+        if *parent_id == BlockId::zero().as_ref() {
+            match txn.get(Table::State, LOG_ID_KEY) {
+                Ok(_) => {
+                    let msg = "initial block already set".to_string();
+                    return Err(ErrorKind::EntryAlreadyExists(msg).into());
+                }
+                Err(Error(ErrorKind::NotFound(_), _)) => (),
+                Err(err) => return Err(err),
+            };
+
+            txn.put(Table::State, LOG_ID_KEY, block_id.as_ref())?;
+            height = 0;
+        } else if *parent_id != self.current_block_id(txn)?.as_ref() {
+            let msg = "new block's parent does not match current ID".to_string();
+            return Err(ErrorKind::OrderingInvalid(msg).into());
+        } else {
+            height = self.current_block_height(txn)? + 1;
+        }
+
+        // This check should be redundant given the one above, but is here just in case
+        match txn.get(Table::Blocks, block_id.as_ref()) {
+            Ok(_) => {
+                let msg = "new block has already been committed".to_string();
+                return Err(ErrorKind::EntryAlreadyExists(msg).into());
+            }
+            Err(Error(ErrorKind::NotFound(_), _)) => (),
+            Err(err) => return Err(err),
+        }
+
+        // Store the new block
+        txn.put(
+            Table::Blocks,
+            block_id.as_ref(),
+            &block.write_to_bytes()?,
+        )?;
+
+        // Index the new block by its height
+        txn.put(Table::Heights, &height_key(height), block_id.as_ref())?;
+
+        // Update the current block ID in the state table
+        txn.put(Table::State, LATEST_BLOCK_ID_KEY, block_id.as_ref())?;
+
+        Ok(())
+    }
 
     /// Obtain the current block ID
     fn current_block_id<'t, T>(&'t self, txn: &'t T) -> Result<BlockId>
     where
-        T: Transaction;
+        T: Transaction,
+    {
+        BlockId::from_bytes(txn.get(Table::State, LATEST_BLOCK_ID_KEY)?)
+    }
+
+    /// Obtain the ID of the initial block in the log
+    fn initial_block_id<'t, T>(&'t self, txn: &'t T) -> Result<BlockId>
+    where
+        T: Transaction,
+    {
+        BlockId::from_bytes(txn.get(Table::State, LOG_ID_KEY)?)
+    }
+
+    /// Find the block with the given ID
+    fn find_block<'t, T>(&'t self, txn: &'t T, id: &BlockId) -> Result<Block>
+    where
+        T: Transaction,
+    {
+        Ok(protobuf::parse_from_bytes::<Block>(
+            txn.get(Table::Blocks, id.as_ref())?,
+        )?)
+    }
+
+    /// Obtain the height of the current block, i.e. the number of blocks preceding it
+    fn current_block_height<'t, T>(&'t self, txn: &'t T) -> Result<u64>
+    where
+        T: Transaction,
+    {
+        match txn.last(Table::Heights)? {
+            Some((height, _)) => Ok(NativeEndian::read_u64(height)),
+            None => Err(ErrorKind::NotFound("log is empty".to_string()).into()),
+        }
+    }
+
+    /// Find the block at the given height (the initial block has height 0)
+    fn find_block_by_height<'t, T>(&'t self, txn: &'t T, height: u64) -> Result<Block>
+    where
+        T: Transaction,
+    {
+        let block_id = match txn.get(Table::Heights, &height_key(height)) {
+            Ok(bytes) => BlockId::from_bytes(bytes)?,
+            Err(Error(ErrorKind::NotFound(_), _)) => {
+                let msg = format!("no block at height {}", height);
+                return Err(ErrorKind::NotFound(msg).into());
+            }
+            Err(err) => return Err(err),
+        };
+
+        self.find_block(txn, &block_id)
+    }
+
+    /// Iterate over the blocks with heights in the given range, in order
+    fn find_blocks<'t, T>(&'t self, txn: &'t T, heights: Range<u64>) -> Blocks<'t, Self, T>
+    where
+        T: Transaction,
+        Self: Sized,
+    {
+        Blocks {
+            adapter: self,
+            txn: txn,
+            heights: heights,
+        }
+    }
 
     /// Add an entry to the database
     fn add_entry<'t>(
@@ -76,20 +326,642 @@ pub trait Adapter<'a> {
         name: &'t str,
         parent_id: EntryId,
         metadata: &Metadata,
-    ) -> Result<DirEntry>;
+    ) -> Result<DirEntry> {
+        match txn.get(Table::Entries, entry.id.as_ref()) {
+            Ok(_) => {
+                let msg = format!(
+                    "error creating '{}': entry ID '{:?}' already exists",
+                    name,
+                    entry.id
+                );
+                return Err(ErrorKind::EntryAlreadyExists(msg).into());
+            }
+            Err(Error(ErrorKind::NotFound(_), _)) => (),
+            Err(err) => return Err(err).chain_err(|| format!("error creating {}", name)),
+        }
+
+        match find_child(txn, parent_id, name) {
+            Ok(_) => {
+                let msg = format!("entry '{}' already exists", name);
+                return Err(ErrorKind::EntryAlreadyExists(msg).into());
+            }
+            Err(Error(ErrorKind::NotFound(_), _)) => (),
+            Err(err) => {
+                return Err(err).chain_err(|| {
+                    format!("error finding child of entry ID {:?}", parent_id)
+                })
+            }
+        }
+
+        let direntry = DirEntry {
+            id: entry.id,
+            parent_id: parent_id,
+            name: name,
+        };
+
+        if entry.id != EntryId::root() {
+            txn.put(
+                Table::Directories,
+                parent_id.as_ref(),
+                &direntry.to_bytes(),
+            )?;
+
+            txn.put(
+                Table::Parents,
+                entry.id.as_ref(),
+                &direntry.to_parent_bytes(),
+            )?;
+
+            txn.put(
+                Table::Children,
+                &child_key(parent_id, name),
+                &direntry.to_bytes(),
+            )?;
+        }
+
+        // Index signature keys so the signers of blocks can be authorized
+        if let Some(public_key) = signer_public_key(entry)? {
+            add_signer(txn, &public_key, entry.id)?;
+        }
+
+        // Index policies by the entry whose subtree they apply to
+        if entry.class == Class::Policy {
+            txn.put(Table::Policies, parent_id.as_ref(), entry.id.as_ref())?;
+        }
+
+        put_entry(txn, entry, metadata)?;
+
+        Ok(direntry)
+    }
+
+    /// Replace an existing entry and its metadata. The entry's class cannot be changed
+    fn update_entry<'t>(
+        &'t self,
+        txn: &'t mut Self::W,
+        entry: &SerializedEntry,
+        metadata: &Metadata,
+    ) -> Result<()> {
+        let old_public_key = {
+            let old_entry = self.find_entry(txn, &entry.id)?;
+
+            if old_entry.class != entry.class {
+                let msg = format!(
+                    "can't replace {:?} with {:?} (entry ID {:?})",
+                    old_entry.class,
+                    entry.class,
+                    entry.id
+                );
+                return Err(ErrorKind::TypeInvalid(msg).into());
+            }
+
+            signer_public_key(&old_entry)?
+        };
+
+        // Keep the signer index in sync with rotated signature keys
+        let new_public_key = signer_public_key(entry)?;
+
+        if old_public_key != new_public_key {
+            if let Some(public_key) = old_public_key {
+                txn.delete(Table::Signers, &public_key, None)?;
+            }
+
+            if let Some(public_key) = new_public_key {
+                add_signer(txn, &public_key, entry.id)?;
+            }
+        }
+
+        put_entry(txn, entry, metadata)
+    }
+
+    /// Move an entry (along with its children) to a new parent and/or name, replacing its
+    /// metadata. The entry keeps its ID
+    fn move_entry<'t>(
+        &'t self,
+        txn: &'t mut Self::W,
+        id: EntryId,
+        parent_id: EntryId,
+        name: &'t str,
+        metadata: &Metadata,
+    ) -> Result<DirEntry> {
+        if id == EntryId::root() {
+            let msg = "the root entry cannot be moved".to_string();
+            return Err(ErrorKind::StructureInvalid(msg).into());
+        }
+
+        match find_child(txn, parent_id, name) {
+            Ok(_) => {
+                let msg = format!("entry '{}' already exists", name);
+                return Err(ErrorKind::EntryAlreadyExists(msg).into());
+            }
+            Err(Error(ErrorKind::NotFound(_), _)) => (),
+            Err(err) => return Err(err),
+        }
+
+        let (old_parent_id, old_direntry_bytes, old_child_key) = {
+            let bytes = txn.get(Table::Parents, id.as_ref()).chain_err(|| {
+                format!("couldn't find parent of entry ID {:?}", id)
+            })?;
+
+            let old_direntry = DirEntry::from_parent_bytes(id, bytes)?;
+            (
+                old_direntry.parent_id,
+                old_direntry.to_bytes(),
+                child_key(old_direntry.parent_id, old_direntry.name),
+            )
+        };
+
+        let class = self.find_entry(txn, &id)?.class;
+
+        let direntry = DirEntry {
+            id: id,
+            parent_id: parent_id,
+            name: name,
+        };
+
+        txn.delete(
+            Table::Directories,
+            old_parent_id.as_ref(),
+            Some(&old_direntry_bytes),
+        )?;
+
+        txn.put(
+            Table::Directories,
+            parent_id.as_ref(),
+            &direntry.to_bytes(),
+        )?;
+
+        txn.put(Table::Parents, id.as_ref(), &direntry.to_parent_bytes())?;
+
+        txn.delete(Table::Children, &old_child_key, None)?;
+        txn.put(
+            Table::Children,
+            &child_key(parent_id, name),
+            &direntry.to_bytes(),
+        )?;
+
+        // Policies apply to the subtree of their (new) parent
+        if class == Class::Policy {
+            txn.delete(
+                Table::Policies,
+                old_parent_id.as_ref(),
+                Some(id.as_ref()),
+            )?;
+            txn.put(Table::Policies, parent_id.as_ref(), id.as_ref())?;
+        }
+
+        txn.put(Table::Metadata, id.as_ref(), &metadata.write_to_bytes()?)?;
+
+        Ok(direntry)
+    }
+
+    /// Delete an entry from the database, leaving its metadata behind as a tombstone recording
+    /// the block which deleted it. Entries with children can only be deleted recursively
+    fn delete_entry<'t>(
+        &'t self,
+        txn: &'t mut Self::W,
+        id: EntryId,
+        block_id: &BlockId,
+        timestamp: Timestamp,
+        recursive: bool,
+    ) -> Result<()> {
+        if id == EntryId::root() {
+            let msg = "the root entry cannot be deleted".to_string();
+            return Err(ErrorKind::StructureInvalid(msg).into());
+        }
+
+        remove_entry(txn, id, block_id, timestamp, recursive)
+    }
 
     /// Find the directory entry (including entry ID) under the given path
     fn find_direntry<'t, T>(&'t self, txn: &'t T, path: &path::Path) -> Result<DirEntry>
     where
-        T: Transaction;
+        T: Transaction,
+    {
+        let result = path.components().iter().fold(
+            Ok(DirEntry::root()),
+            |parent_direntry, component| find_child(txn, parent_direntry?.id, component),
+        );
+
+        match result {
+            Ok(_) => result,
+            Err(Error(ErrorKind::NotFound(_), _)) => {
+                Err(ErrorKind::NotFound(path.to_string()).into())
+            }
+            Err(_) => result.chain_err(|| format!("error reading {:?}", path)),
+        }
+    }
+
+    /// List the directory entries of the children of the given entry
+    fn list_children<'t, T>(&'t self, txn: &'t T, parent_id: EntryId) -> Result<Vec<DirEntry>>
+    where
+        T: Transaction,
+    {
+        read_children(txn, parent_id)
+    }
+
+    /// Walk the subtree beneath the given path depth-first, yielding the path and directory
+    /// entry of every descendant (but not of the entry at the path itself)
+    fn walk<'t, T>(&'t self, txn: &'t T, path: &path::Path) -> Result<Walk<'t, Self, T>>
+    where
+        T: Transaction,
+        Self: Sized,
+    {
+        let direntry = self.find_direntry(txn, path)?;
+
+        let mut walk = Walk {
+            adapter: self,
+            txn: txn,
+            stack: Vec::new(),
+        };
+
+        walk.push_children(&path.to_owned(), direntry.id)?;
+        Ok(walk)
+    }
 
     /// Find the metadata associated with a given entry ID
     fn find_metadata<'t, T>(&'t self, txn: &'t T, id: &EntryId) -> Result<Metadata>
     where
-        T: Transaction;
+        T: Transaction,
+    {
+        read_metadata(txn, id)
+    }
 
     /// Find the serialized entry under a given entry ID
     fn find_entry<'t, T>(&'t self, txn: &'t T, id: &EntryId) -> Result<SerializedEntry>
     where
-        T: Transaction;
+        T: Transaction,
+    {
+        read_entry(txn, id)
+    }
+
+    /// Find the path to the entry with the given ID
+    fn find_path<'t, T>(&'t self, txn: &'t T, id: &EntryId) -> Result<path::PathBuf>
+    where
+        T: Transaction,
+    {
+        let mut names = Vec::new();
+        let mut current_id = *id;
+
+        while current_id != EntryId::root() {
+            let bytes = txn.get(Table::Parents, current_id.as_ref()).chain_err(
+                || format!("couldn't find parent of entry ID {:?}", current_id),
+            )?;
+
+            let direntry = DirEntry::from_parent_bytes(current_id, bytes)?;
+            names.push(direntry.name);
+            current_id = direntry.parent_id;
+        }
+
+        let mut path = path::PathBuf::new();
+
+        for name in names.iter().rev() {
+            path.push(name);
+        }
+
+        Ok(path)
+    }
+
+    /// Find the IDs of the policies which apply to the subtree of the given entry
+    fn find_policies<'t, T>(&'t self, txn: &'t T, id: &EntryId) -> Result<Vec<EntryId>>
+    where
+        T: Transaction,
+    {
+        txn.get_all(Table::Policies, id.as_ref())?
+            .iter()
+            .map(|bytes| EntryId::from_bytes(bytes))
+            .collect()
+    }
+
+    /// Find the ID of the signature key pair credential with the given public key
+    fn find_signer<'t, T>(&'t self, txn: &'t T, public_key: &[u8]) -> Result<EntryId>
+    where
+        T: Transaction,
+    {
+        match txn.get(Table::Signers, public_key) {
+            Ok(bytes) => EntryId::from_bytes(bytes),
+            Err(Error(ErrorKind::NotFound(_), _)) => {
+                let msg = "no credential with the given public key".to_string();
+                Err(ErrorKind::NotFound(msg).into())
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Record the paths of the credentials which signed the given block (in the same order as
+    /// its signatures), so they can be found even after the credentials change
+    fn add_block_signers<'t>(
+        &'t self,
+        txn: &'t mut Self::W,
+        block: &Block,
+        signers: &[path::PathBuf],
+    ) -> Result<()> {
+        let block_id = BlockId::of(block);
+        let signatures = block.get_witness().get_signatures();
+
+        for (signature, signer) in signatures.iter().zip(signers) {
+            txn.put(
+                Table::Witnesses,
+                &witness_key(&block_id, signature.get_public_key()),
+                signer.as_path().to_string().as_bytes(),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Find the path of the credential with the given public key as of when it signed the
+    /// given block
+    fn find_block_signer<'t, T>(
+        &'t self,
+        txn: &'t T,
+        block_id: &BlockId,
+        public_key: &[u8],
+    ) -> Result<path::PathBuf>
+    where
+        T: Transaction,
+    {
+        let bytes = txn.get(Table::Witnesses, &witness_key(block_id, public_key))?;
+        let signer = str::from_utf8(bytes).map_err(|err| {
+            Error::from(ErrorKind::ParseFailure(err.to_string()))
+        })?;
+
+        Ok(path::PathBuf::from(signer.to_owned()))
+    }
+
+    /// Compare the raw contents of every table in this database with another database (which
+    /// may use a different adapter), returning the names of the tables whose keys or values differ
+    fn compare_tables<'b, B>(&'a self, other: &'b B) -> Result<Vec<&'static str>>
+    where
+        B: Adapter<'b>,
+    {
+        let txn = self.ro_transaction()?;
+        let other_txn = other.ro_transaction()?;
+        let mut mismatched = Vec::new();
+
+        for table in &TABLES {
+            // Adapters needn't agree on how keys are ordered, only on the contents
+            let mut pairs = txn.pairs(*table)?;
+            let mut other_pairs = other_txn.pairs(*table)?;
+            pairs.sort();
+            other_pairs.sort();
+
+            if pairs != other_pairs {
+                mismatched.push(table.name());
+            }
+        }
+
+        Ok(mismatched)
+    }
+}
+
+/// Iterator over the blocks in a range of heights, which reads each block as it is reached
+pub struct Blocks<'t, A: 't, T: 't> {
+    adapter: &'t A,
+    txn: &'t T,
+    heights: Range<u64>,
+}
+
+impl<'a, 't, A, T> Iterator for Blocks<'t, A, T>
+where
+    A: Adapter<'a> + 't,
+    T: Transaction + 't,
+{
+    type Item = Result<Block>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.heights
+            .next()
+            .map(|height| self.adapter.find_block_by_height(self.txn, height))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.heights.size_hint()
+    }
+}
+
+impl<'a, 't, A, T> DoubleEndedIterator for Blocks<'t, A, T>
+where
+    A: Adapter<'a> + 't,
+    T: Transaction + 't,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.heights
+            .next_back()
+            .map(|height| self.adapter.find_block_by_height(self.txn, height))
+    }
+}
+
+/// Depth-first iterator over the entries in a subtree of the directory
+pub struct Walk<'t, A: 't, T: 't> {
+    adapter: &'t A,
+    txn: &'t T,
+    stack: Vec<(path::PathBuf, DirEntry<'t>)>,
+}
+
+impl<'a, 't, A, T> Walk<'t, A, T>
+where
+    A: Adapter<'a> + 't,
+    T: Transaction + 't,
+{
+    /// Queue the children of the given entry to be visited next, in order
+    fn push_children(&mut self, path: &path::PathBuf, id: EntryId) -> Result<()> {
+        let children = self.adapter.list_children(self.txn, id)?;
+
+        for child in children.into_iter().rev() {
+            let mut child_path = path.clone();
+            child_path.push(child.name);
+            self.stack.push((child_path, child));
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a, 't, A, T> Iterator for Walk<'t, A, T>
+where
+    A: Adapter<'a> + 't,
+    T: Transaction + 't,
+{
+    type Item = Result<(path::PathBuf, DirEntry<'t>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (path, direntry) = match self.stack.pop() {
+            Some(next) => next,
+            None => return None,
+        };
+
+        if let Err(err) = self.push_children(&path, direntry.id) {
+            self.stack.clear();
+            return Some(Err(err));
+        }
+
+        Some(Ok((path, direntry)))
+    }
+}
+
+/// Read the serialized entry with the given ID
+fn read_entry<'t, T: Transaction>(txn: &'t T, id: &EntryId) -> Result<SerializedEntry<'t>> {
+    let bytes = txn.get(Table::Entries, id.as_ref())?;
+    SerializedEntry::from_bytes(*id, bytes)
+}
+
+/// Read the metadata of the entry with the given ID
+fn read_metadata<T: Transaction>(txn: &T, id: &EntryId) -> Result<Metadata> {
+    let proto = txn.get(Table::Metadata, id.as_ref())?;
+    Ok(protobuf::parse_from_bytes::<Metadata>(proto)?)
+}
+
+/// Read the directory entries of the children of the given entry
+fn read_children<T: Transaction>(txn: &T, parent_id: EntryId) -> Result<Vec<DirEntry>> {
+    txn.get_all(Table::Directories, parent_id.as_ref())?
+        .iter()
+        .map(|direntry_bytes| DirEntry::new(parent_id, direntry_bytes))
+        .collect()
+}
+
+/// Find the child of the given entry with the given name
+fn find_child<'t, T>(txn: &'t T, parent_id: EntryId, name: &str) -> Result<DirEntry<'t>>
+where
+    T: Transaction,
+{
+    DirEntry::new(parent_id, txn.get(Table::Children, &child_key(parent_id, name))?)
+}
+
+/// Index a signature key pair credential by its public key
+fn add_signer<W: WriteTransaction>(txn: &mut W, public_key: &[u8], id: EntryId) -> Result<()> {
+    match txn.get(Table::Signers, public_key) {
+        Ok(_) => {
+            let msg = "a credential with this public key already exists".to_string();
+            return Err(ErrorKind::EntryAlreadyExists(msg).into());
+        }
+        Err(Error(ErrorKind::NotFound(_), _)) => (),
+        Err(err) => return Err(err),
+    }
+
+    txn.put(Table::Signers, public_key, id.as_ref())
+}
+
+/// Store an entry and its metadata, overwriting any previous values
+fn put_entry<W>(txn: &mut W, entry: &SerializedEntry, metadata: &Metadata) -> Result<()>
+where
+    W: WriteTransaction,
+{
+    txn.put(
+        Table::Metadata,
+        entry.id.as_ref(),
+        &metadata.write_to_bytes()?,
+    )?;
+
+    let mut bytes = Vec::with_capacity(entry::HEADER_SIZE + entry.data.len());
+    bytes.extend_from_slice(&entry.class.as_bytes());
+    bytes.extend_from_slice(entry.data);
+
+    txn.put(Table::Entries, entry.id.as_ref(), &bytes)
+}
+
+/// Remove an entry and (if requested) its children, leaving tombstones in the metadata table
+fn remove_entry<W: WriteTransaction>(
+    txn: &mut W,
+    id: EntryId,
+    block_id: &BlockId,
+    timestamp: Timestamp,
+    recursive: bool,
+) -> Result<()> {
+    let children: Vec<EntryId> = read_children(txn, id)?
+        .iter()
+        .map(|direntry| direntry.id)
+        .collect();
+
+    if !children.is_empty() && !recursive {
+        let msg = format!("entry ID {:?} has {} children", id, children.len());
+        return Err(ErrorKind::StructureInvalid(msg).into());
+    }
+
+    for child_id in children {
+        remove_entry(txn, child_id, block_id, timestamp, recursive)?;
+    }
+
+    let (parent_id, direntry_bytes, direntry_key) = {
+        let bytes = txn.get(Table::Parents, id.as_ref()).chain_err(|| {
+            format!("couldn't find parent of entry ID {:?}", id)
+        })?;
+
+        let direntry = DirEntry::from_parent_bytes(id, bytes)?;
+        (
+            direntry.parent_id,
+            direntry.to_bytes(),
+            child_key(direntry.parent_id, direntry.name),
+        )
+    };
+
+    let (class, public_key) = {
+        let entry = read_entry(txn, &id)?;
+        (entry.class, signer_public_key(&entry)?)
+    };
+
+    if let Some(public_key) = public_key {
+        txn.delete(Table::Signers, &public_key, None)?;
+    }
+
+    if class == Class::Policy {
+        txn.delete(Table::Policies, parent_id.as_ref(), Some(id.as_ref()))?;
+    }
+
+    txn.delete(
+        Table::Directories,
+        parent_id.as_ref(),
+        Some(&direntry_bytes),
+    )?;
+    txn.delete(Table::Children, &direntry_key, None)?;
+    txn.delete(Table::Parents, id.as_ref(), None)?;
+    txn.delete(Table::Entries, id.as_ref(), None)?;
+
+    let mut metadata = read_metadata(txn, &id)?;
+    metadata.set_deleted_id(Vec::from(block_id.as_ref()));
+    metadata.set_deleted_at(timestamp.to_int());
+
+    txn.put(Table::Metadata, id.as_ref(), &metadata.write_to_bytes()?)
+}
+
+/// Serialize the key a child is indexed under in the children table: its parent's ID followed by
+/// its name
+fn child_key(parent_id: EntryId, name: &str) -> Vec<u8> {
+    let mut key = Vec::with_capacity(ENTRY_ID_SIZE + name.len());
+    key.extend_from_slice(parent_id.as_ref());
+    key.extend_from_slice(name.as_bytes());
+    key
+}
+
+/// Serialize the key a block's signer is recorded under in the witnesses table: the block's ID
+/// followed by the signer's public key
+fn witness_key(block_id: &BlockId, public_key: &[u8]) -> Vec<u8> {
+    let mut key = Vec::with_capacity(block_id.as_ref().len() + public_key.len());
+    key.extend_from_slice(block_id.as_ref());
+    key.extend_from_slice(public_key);
+    key
+}
+
+/// Serialize a block height as a host-native integer key
+fn height_key(height: u64) -> [u8; 8] {
+    let mut key = [0u8; 8];
+    NativeEndian::write_u64(&mut key, height);
+    key
+}
+
+/// Obtain the public key of the given entry if it's a signature key pair credential
+fn signer_public_key(entry: &SerializedEntry) -> Result<Option<Vec<u8>>> {
+    if entry.class != Class::Credential {
+        return Ok(None);
+    }
+
+    match entry.deserialize()? {
+        Entry::Credential(credential) => {
+            if credential.credential_type == credential::Type::SIGNATURE_KEY_PAIR {
+                Ok(Some(credential.public_key))
+            } else {
+                Ok(None)
+            }
+        }
+        _ => Ok(None),
+    }
 }
@@ -0,0 +1,381 @@
+//! adapter/tests.rs: Test suite shared by every storage adapter
+//!
+//! Each adapter's tests module defines a `create_database()` function returning an empty
+//! database, then invokes `adapter_tests!()` to generate the suite.
+//!
+
+use alg::CipherSuite;
+use block::Block;
+use crypto::signing::KeyPair;
+use entry::{Class, SerializedEntry};
+use id::{BlockId, EntryId};
+use metadata::Metadata;
+use ring::rand;
+use setup;
+use timestamp::Timestamp;
+
+pub fn example_timestamp() -> Timestamp {
+    Timestamp::at(1_231_006_505)
+}
+
+pub fn example_block() -> Block {
+    let rng = rand::SystemRandom::new();
+    let admin_keypair = KeyPair::generate(&rng);
+
+    setup::create_log(
+        CipherSuite::Ed25519_AES256GCM_SHA256,
+        setup::tests::ADMIN_USERNAME,
+        &admin_keypair,
+        setup::tests::ADMIN_KEYPAIR_SEALED,
+        setup::tests::ADMIN_KEYPAIR_SALT,
+        setup::tests::COMMENT,
+    )
+}
+
+pub fn example_metadata() -> Metadata {
+    let mut metadata = Metadata::new();
+
+    let block_id = BlockId::zero();
+
+    metadata.set_created_id(Vec::from(block_id.as_ref()));
+    metadata.set_updated_id(Vec::from(block_id.as_ref()));
+    metadata.set_created_at(example_timestamp().to_int());
+    metadata.set_updated_at(example_timestamp().to_int());
+
+    metadata
+}
+
+pub fn example_entry(id: EntryId, data: &[u8]) -> SerializedEntry {
+    SerializedEntry {
+        id: id,
+        class: Class::Root,
+        data: data,
+    }
+}
+
+/// Generate the shared adapter test suite in a `suite` submodule of the calling module
+macro_rules! adapter_tests {
+    () => {
+        mod suite {
+            use super::create_database;
+            use adapter::{Adapter, Transaction};
+            use adapter::tests::{example_block, example_entry, example_metadata,
+                                 example_timestamp};
+            use block::{Block, Body};
+            use errors::*;
+            use id::{BlockId, EntryId};
+            use path::Path;
+
+            #[test]
+            fn duplicate_block() {
+                let adapter = create_database();
+                let block = example_block();
+
+                let mut txn = adapter.rw_transaction().unwrap();
+                adapter.add_block(&mut txn, &block).unwrap();
+                txn.commit().unwrap();
+
+                let mut txn = adapter.rw_transaction().unwrap();
+                let err = adapter.add_block(&mut txn, &block).expect_err(
+                    "expected duplicate block to cause error",
+                );
+
+                match *err.kind() {
+                    ErrorKind::EntryAlreadyExists(ref msg) => {
+                        assert_eq!(msg, "initial block already set")
+                    }
+                    ref other => panic!("unexpected error kind: {:?}", other),
+                }
+            }
+
+            #[test]
+            fn block_heights() {
+                let adapter = create_database();
+                let block1 = example_block();
+
+                let mut body = Body::new();
+                body.set_parent_id(Vec::from(BlockId::of(&block1).as_ref()));
+                body.set_timestamp(example_timestamp().to_int());
+
+                let mut block2 = Block::new();
+                block2.set_body(body);
+
+                let mut txn = adapter.rw_transaction().unwrap();
+                adapter.add_block(&mut txn, &block1).unwrap();
+                adapter.add_block(&mut txn, &block2).unwrap();
+                txn.commit().unwrap();
+
+                let txn = adapter.ro_transaction().unwrap();
+                assert_eq!(adapter.current_block_height(&txn).unwrap(), 1);
+                assert_eq!(adapter.find_block_by_height(&txn, 0).unwrap(), block1);
+                assert_eq!(adapter.find_block_by_height(&txn, 1).unwrap(), block2);
+                assert_eq!(
+                    adapter
+                        .find_blocks(&txn, 0..2)
+                        .collect::<Result<Vec<_>>>()
+                        .unwrap(),
+                    vec![block1.clone(), block2.clone()]
+                );
+                assert_eq!(
+                    adapter
+                        .find_blocks(&txn, 0..2)
+                        .rev()
+                        .collect::<Result<Vec<_>>>()
+                        .unwrap(),
+                    vec![block2.clone(), block1]
+                );
+                assert_eq!(
+                    adapter
+                        .find_block(&txn, &BlockId::of(&block2))
+                        .unwrap(),
+                    block2
+                );
+
+                match *adapter.find_block_by_height(&txn, 2).unwrap_err().kind() {
+                    ErrorKind::NotFound(_) => (),
+                    ref other => panic!("unexpected error kind: {:?}", other),
+                }
+            }
+
+            #[test]
+            fn entry_lookup() {
+                let adapter = create_database();
+                let example_data = b"just an example host entry";
+
+                {
+                    let mut txn = adapter.rw_transaction().unwrap();
+
+                    let domain_id = adapter.next_free_entry_id(&txn).unwrap();
+                    adapter
+                        .add_entry(
+                            &mut txn,
+                            &example_entry(domain_id, b"example domain entry"),
+                            "example.com",
+                            EntryId::root(),
+                            &example_metadata(),
+                        )
+                        .unwrap();
+
+                    let hosts_id = domain_id.next();
+                    adapter
+                        .add_entry(
+                            &mut txn,
+                            &example_entry(hosts_id, b"example hosts ou"),
+                            "hosts",
+                            domain_id,
+                            &example_metadata(),
+                        )
+                        .unwrap();
+
+                    let host_id = hosts_id.next();
+                    adapter
+                        .add_entry(
+                            &mut txn,
+                            &example_entry(host_id, example_data),
+                            "master.example.com",
+                            hosts_id,
+                            &example_metadata(),
+                        )
+                        .unwrap();
+
+                    txn.commit().unwrap();
+                }
+
+                {
+                    let txn = adapter.ro_transaction().unwrap();
+
+                    {
+                        let path = Path::new("/example.com/hosts/master.example.com").unwrap();
+
+                        let direntry = adapter.find_direntry(&txn, path).unwrap();
+                        assert_eq!(direntry.name, "master.example.com");
+
+                        let metadata = adapter.find_metadata(&txn, &direntry.id).unwrap();
+                        assert_eq!(metadata.created_at, example_timestamp().to_int());
+
+                        let entry = adapter.find_entry(&txn, &direntry.id).unwrap();
+                        assert_eq!(entry.data, &example_data[..]);
+
+                        let entry_path = adapter.find_path(&txn, &direntry.id).unwrap();
+                        assert_eq!(entry_path.as_path(), path);
+                    }
+
+                    txn.commit().unwrap();
+                }
+            }
+
+            #[test]
+            fn duplicate_entry_id() {
+                let adapter = create_database();
+
+                let mut txn = adapter.rw_transaction().unwrap();
+
+                let domain_id = adapter.next_free_entry_id(&txn).unwrap();
+                adapter
+                    .add_entry(
+                        &mut txn,
+                        &example_entry(domain_id, b"domain"),
+                        "example.com",
+                        EntryId::root(),
+                        &example_metadata(),
+                    )
+                    .unwrap();
+
+                let err = adapter
+                    .add_entry(
+                        &mut txn,
+                        &example_entry(domain_id, b"domain"),
+                        "another.com",
+                        EntryId::root(),
+                        &example_metadata(),
+                    )
+                    .expect_err("expected duplicate entry ID to cause error");
+
+                match *err.kind() {
+                    ErrorKind::EntryAlreadyExists(_) => (),
+                    ref other => panic!("unexpected error kind: {:?}", other),
+                }
+            }
+
+            #[test]
+            fn duplicate_entry_name() {
+                let adapter = create_database();
+
+                let mut txn = adapter.rw_transaction().unwrap();
+
+                let domain_id = adapter.next_free_entry_id(&txn).unwrap();
+                adapter
+                    .add_entry(
+                        &mut txn,
+                        &example_entry(domain_id, b"domain"),
+                        "example.com",
+                        EntryId::root(),
+                        &example_metadata(),
+                    )
+                    .unwrap();
+
+                let err = adapter
+                    .add_entry(
+                        &mut txn,
+                        &example_entry(domain_id.next(), b"domain"),
+                        "example.com",
+                        EntryId::root(),
+                        &example_metadata(),
+                    )
+                    .expect_err("expected duplicate entry name to cause error");
+
+                match *err.kind() {
+                    ErrorKind::EntryAlreadyExists(_) => (),
+                    ref other => panic!("unexpected error kind: {:?}", other),
+                }
+            }
+
+            #[test]
+            fn deleted_entry_tombstone() {
+                let adapter = create_database();
+
+                let mut txn = adapter.rw_transaction().unwrap();
+
+                let domain_id = adapter.next_free_entry_id(&txn).unwrap();
+                adapter
+                    .add_entry(
+                        &mut txn,
+                        &example_entry(domain_id, b"domain"),
+                        "example.com",
+                        EntryId::root(),
+                        &example_metadata(),
+                    )
+                    .unwrap();
+
+                let block_id = BlockId::of(&example_block());
+                adapter
+                    .delete_entry(&mut txn, domain_id, &block_id, example_timestamp(), false)
+                    .unwrap();
+
+                let path = Path::new("/example.com").unwrap();
+
+                match *adapter.find_direntry(&txn, path).unwrap_err().kind() {
+                    ErrorKind::NotFound(ref msg) => assert_eq!(msg, "/example.com"),
+                    ref other => panic!("unexpected error kind: {:?}", other),
+                }
+
+                let metadata = adapter.find_metadata(&txn, &domain_id).unwrap();
+                assert_eq!(metadata.get_deleted_id(), block_id.as_ref());
+                assert_eq!(metadata.get_deleted_at(), example_timestamp().to_int());
+
+                // Deleted entry IDs are never reused
+                assert_eq!(adapter.next_free_entry_id(&txn).unwrap(), domain_id.next());
+            }
+
+            #[test]
+            fn child_lookup() {
+                let adapter = create_database();
+
+                let mut txn = adapter.rw_transaction().unwrap();
+                let alpha_id = adapter.next_free_entry_id(&txn).unwrap();
+                let bravo_id = alpha_id.next();
+                let charlie_id = bravo_id.next();
+
+                for &(id, name) in &[
+                    (alpha_id, "alpha.com"),
+                    (bravo_id, "bravo.com"),
+                    (charlie_id, "charlie.com"),
+                ]
+                {
+                    adapter
+                        .add_entry(
+                            &mut txn,
+                            &example_entry(id, b"domain"),
+                            name,
+                            EntryId::root(),
+                            &example_metadata(),
+                        )
+                        .unwrap();
+                }
+
+                adapter
+                    .move_entry(&mut txn, bravo_id, alpha_id, "delta.com", &example_metadata())
+                    .unwrap();
+
+                let block_id = BlockId::of(&example_block());
+                adapter
+                    .delete_entry(&mut txn, charlie_id, &block_id, example_timestamp(), false)
+                    .unwrap();
+
+                // Children are found by name after being moved, and not after being deleted
+                for &(path, expected_id) in &[
+                    ("/alpha.com", Some(alpha_id)),
+                    ("/alpha.com/delta.com", Some(bravo_id)),
+                    ("/bravo.com", None),
+                    ("/charlie.com", None),
+                ]
+                {
+                    let result = adapter.find_direntry(&txn, Path::new(path).unwrap());
+
+                    match expected_id {
+                        Some(id) => assert_eq!(result.unwrap().id, id),
+                        None => {
+                            match *result.unwrap_err().kind() {
+                                ErrorKind::NotFound(_) => (),
+                                ref other => panic!("unexpected error kind: {:?}", other),
+                            }
+                        }
+                    }
+                }
+            }
+
+            #[test]
+            fn aborted_writes() {
+                let adapter = create_database();
+
+                let mut txn = adapter.rw_transaction().unwrap();
+                adapter.add_block(&mut txn, &example_block()).unwrap();
+                assert!(adapter.current_block_id(&txn).is_ok());
+                txn.abort();
+
+                let txn = adapter.ro_transaction().unwrap();
+                assert!(adapter.current_block_id(&txn).is_err());
+            }
+        }
+    };
+}
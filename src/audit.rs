@@ -0,0 +1,148 @@
+//! audit.rs: Summaries of the blocks in the log, for answering questions about who changed what
+//!
+//! Every change to the directory is made by a block, so the history of any entry (or subtree of
+//! entries) can be reconstructed by selecting the ops in the log which touched it. Entries keep
+//! their history when moved, so the log is searched under whichever path they had at the time.
+//!
+
+use id::BlockId;
+use op::{self, Op};
+use path::{Path, PathBuf};
+use timestamp::Timestamp;
+
+/// A block in the log, along with the ops it contains which are of interest
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    /// ID of the block
+    pub block_id: BlockId,
+
+    /// Time at which the block claims to have been created
+    pub timestamp: Timestamp,
+
+    /// Keys which signed the block
+    pub signers: Vec<LogSigner>,
+
+    /// Comment explaining the changes in the block
+    pub comment: String,
+
+    /// Ops in the block (only those affecting the requested subtree, if one was given)
+    pub ops: Vec<Op>,
+}
+
+/// A key which signed a block
+#[derive(Debug, Clone)]
+pub struct LogSigner {
+    /// Raw Ed25519 public key
+    pub public_key: Vec<u8>,
+
+    /// Path to the credential for this key as of when the block was applied (if recorded)
+    pub credential_path: Option<PathBuf>,
+}
+
+/// Does the given op affect the entry at the given path or anything underneath it?
+pub fn affects(op: &Op, path: &Path) -> bool {
+    let op_path = match Path::new(op.get_path()) {
+        Some(op_path) => op_path,
+        None => return false,
+    };
+
+    if op_path.starts_with(path) {
+        return true;
+    }
+
+    match op.get_optype() {
+        // Deleting or moving an entry also deletes or moves everything beneath it
+        op::Type::DELETE | op::Type::MOVE if path.starts_with(op_path) => true,
+        op::Type::MOVE => {
+            Path::new(op.get_destination()).map_or(false, |dest| dest.starts_with(path))
+        }
+        _ => false,
+    }
+}
+
+/// Find where the entry at the given path was before the given op, if the op moved it (or one
+/// of the entries above it) there
+pub fn path_before(op: &Op, path: &Path) -> Option<PathBuf> {
+    if op.get_optype() != op::Type::MOVE {
+        return None;
+    }
+
+    let (source, destination) = match (Path::new(op.get_path()), Path::new(op.get_destination())) {
+        (Some(source), Some(destination)) => (source, destination),
+        _ => return None,
+    };
+
+    if !path.starts_with(destination) {
+        return None;
+    }
+
+    let mut before = source.to_owned();
+
+    for component in &path.components()[destination.components().len()..] {
+        before.push(component);
+    }
+
+    Some(before)
+}
+
+/// Describe an op in a single line, e.g. "MOVE /example.com/a -> /example.com/b"
+pub fn describe(op: &Op) -> String {
+    match op.get_optype() {
+        op::Type::ADD => format!("ADD {}", op.get_path()),
+        op::Type::UPDATE => format!("UPDATE {}", op.get_path()),
+        op::Type::DELETE if op.get_recursive() => format!("DELETE {} (recursive)", op.get_path()),
+        op::Type::DELETE => format!("DELETE {}", op.get_path()),
+        op::Type::MOVE => format!("MOVE {} -> {}", op.get_path(), op.get_destination()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use audit;
+    use op::{self, Op};
+    use path::Path;
+
+    fn example_op(optype: op::Type, path: &str) -> Op {
+        let mut op = Op::new();
+        op.set_optype(optype);
+        op.set_path(path.to_string());
+        op
+    }
+
+    #[test]
+    fn ops_affecting_subtree() {
+        let subtree = Path::new("/example.com/eng").unwrap();
+
+        assert!(audit::affects(&example_op(op::Type::ADD, "/example.com/eng"), subtree));
+        assert!(audit::affects(&example_op(op::Type::ADD, "/example.com/eng/a"), subtree));
+        assert!(!audit::affects(&example_op(op::Type::ADD, "/example.com/engx"), subtree));
+        assert!(!audit::affects(&example_op(op::Type::UPDATE, "/example.com"), subtree));
+        assert!(audit::affects(&example_op(op::Type::DELETE, "/example.com"), subtree));
+
+        let mut move_op = example_op(op::Type::MOVE, "/other.com/ops");
+        move_op.set_destination("/example.com/eng/ops".to_string());
+        assert!(audit::affects(&move_op, subtree));
+        assert_eq!(
+            audit::describe(&move_op),
+            "MOVE /other.com/ops -> /example.com/eng/ops"
+        );
+    }
+
+    #[test]
+    fn paths_before_moves() {
+        let mut move_op = example_op(op::Type::MOVE, "/other.com/ops");
+        move_op.set_destination("/example.com/eng/ops".to_string());
+
+        let moved = Path::new("/example.com/eng/ops").unwrap();
+        let before = audit::path_before(&move_op, moved).unwrap();
+        assert_eq!(before.as_path(), Path::new("/other.com/ops").unwrap());
+
+        let child = Path::new("/example.com/eng/ops/deploy").unwrap();
+        let before = audit::path_before(&move_op, child).unwrap();
+        assert_eq!(before.as_path(), Path::new("/other.com/ops/deploy").unwrap());
+
+        let unmoved = Path::new("/example.com/eng").unwrap();
+        assert!(audit::path_before(&move_op, unmoved).is_none());
+        assert!(audit::path_before(&example_op(op::Type::ADD, "/example.com"), moved).is_none());
+    }
+}
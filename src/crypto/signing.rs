@@ -20,6 +20,9 @@ use signature::Signature;
 use untrusted;
 use witness::Witness;
 
+/// Domain separation prefix for the messages witnesses sign over block bodies
+const BLOCK_BODY_PREFIX: &str = "ithos.block.body.ni:///sha-256;";
+
 /// Digital signature keypair (includes public and private key)
 pub struct KeyPair {
     /// The signature algorithm this key supports
@@ -115,10 +118,7 @@ impl<'a> KeyPair {
 
     /// Sign the body of a block, returning a complete block with signature/witness data
     pub fn sign_block(&self, body: Body) -> Block {
-        let mut message = String::from("ithos.block.body.ni:///sha-256;");
-        message.push_str(&BASE64URL.encode(objecthash::digest(&body).as_ref()));
-
-        let signature = self.sign_raw_bytes(message.as_bytes());
+        let signature = self.sign_raw_bytes(block_body_message(&body).as_bytes());
         let mut witness = Witness::new();
         witness.set_signatures(RepeatedField::from_vec(vec![signature]));
 
@@ -128,6 +128,28 @@ impl<'a> KeyPair {
         block
     }
 
+    /// Add this key's signature to the witness of an unsigned or partially signed block
+    pub fn cosign_block(&self, block: &mut Block) -> Result<()> {
+        let already_signed = block
+            .get_witness()
+            .get_signatures()
+            .iter()
+            .any(|signature| signature.public_key == self.public_key_bytes());
+
+        if already_signed {
+            let msg = format!(
+                "block already signed by {}",
+                BASE64URL.encode(self.public_key_bytes())
+            );
+            return Err(ErrorKind::SignatureInvalid(msg).into());
+        }
+
+        let signature = self.sign_raw_bytes(block_body_message(block.get_body()).as_bytes());
+        block.mut_witness().mut_signatures().push(signature);
+
+        Ok(())
+    }
+
     /// Compute a signature on a raw byte vector
     /// We avoid exposing this directly for domain separation reasons
     fn sign_raw_bytes(&self, msg: &[u8]) -> Signature {
@@ -141,11 +163,68 @@ impl<'a> KeyPair {
     }
 }
 
+/// Verify every signature in a block's witness against the block's body, ensuring each
+/// key signed it only once
+pub fn verify_block(block: &Block) -> Result<()> {
+    let signatures = block.get_witness().get_signatures();
+
+    if signatures.is_empty() {
+        return Err(
+            ErrorKind::SignatureInvalid("block has no signatures".to_string()).into(),
+        );
+    }
+
+    let message = block_body_message(block.get_body());
+
+    for (i, signature) in signatures.iter().enumerate() {
+        let duplicate = signatures[..i].iter().any(|other| {
+            other.public_key == signature.public_key
+        });
+
+        if duplicate {
+            let msg = format!(
+                "duplicate signature by {}",
+                BASE64URL.encode(&signature.public_key)
+            );
+            return Err(ErrorKind::SignatureInvalid(msg).into());
+        }
+
+        verify_raw_bytes(signature, message.as_bytes())?;
+    }
+
+    Ok(())
+}
+
+/// Compute the message which witnesses sign for a given block body
+fn block_body_message(body: &Body) -> String {
+    let mut message = String::from(BLOCK_BODY_PREFIX);
+    message.push_str(&BASE64URL.encode(objecthash::digest(body).as_ref()));
+    message
+}
+
+/// Verify a signature on a raw byte vector
+fn verify_raw_bytes(signature: &Signature, msg: &[u8]) -> Result<()> {
+    // Ed25519 is the only signature algorithm we presently support
+    assert_eq!(signature.algorithm, SignatureAlg::Ed25519);
+
+    ring::signature::verify(
+        &ring::signature::ED25519,
+        untrusted::Input::from(&signature.public_key),
+        untrusted::Input::from(msg),
+        untrusted::Input::from(&signature.value),
+    ).map_err(|_| {
+        let msg = format!("bad signature by {}", BASE64URL.encode(&signature.public_key));
+        ErrorKind::SignatureInvalid(msg).into()
+    })
+}
+
 #[cfg(test)]
 pub mod tests {
     use alg::{EncryptionAlg, SignatureAlg};
-    use crypto::signing::KeyPair;
+    use block::{Block, Body};
+    use crypto::signing::{self, KeyPair};
     use crypto::symmetric::{AES256GCM_KEY_SIZE, AES256GCM_NONCE_SIZE};
+    use errors::*;
     use ring::rand;
 
     // WARNING: Please don't ever use zeroes as an actual encryption key
@@ -176,4 +255,52 @@ pub mod tests {
             unsealed_keypair.public_key_bytes()
         );
     }
+
+    #[test]
+    fn test_signing_and_verifying_blocks() {
+        let rng = rand::SystemRandom::new();
+        let keypair = KeyPair::generate(&rng);
+
+        let mut body = Body::new();
+        body.set_comment("Signed and sealed".to_owned());
+
+        let mut block = keypair.sign_block(body);
+        signing::verify_block(&block).unwrap();
+
+        block.mut_body().set_comment("Tampered with".to_owned());
+
+        match *signing::verify_block(&block)
+            .expect_err("expected tampered block to fail verification")
+            .kind() {
+            ErrorKind::SignatureInvalid(_) => (),
+            ref other => panic!("unexpected error kind: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_cosigning_blocks() {
+        let rng = rand::SystemRandom::new();
+        let keypair1 = KeyPair::generate(&rng);
+        let keypair2 = KeyPair::generate(&rng);
+
+        let mut block = Block::new();
+        block.mut_body().set_comment("Two-person control".to_owned());
+
+        keypair1.cosign_block(&mut block).unwrap();
+        keypair2.cosign_block(&mut block).unwrap();
+        assert_eq!(block.get_witness().get_signatures().len(), 2);
+        signing::verify_block(&block).unwrap();
+
+        assert!(keypair1.cosign_block(&mut block).is_err());
+
+        let duplicate = block.get_witness().get_signatures()[0].clone();
+        block.mut_witness().mut_signatures().push(duplicate);
+
+        match *signing::verify_block(&block)
+            .expect_err("expected duplicate signature to fail verification")
+            .kind() {
+            ErrorKind::SignatureInvalid(_) => (),
+            ref other => panic!("unexpected error kind: {:?}", other),
+        }
+    }
 }
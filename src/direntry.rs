@@ -50,6 +50,17 @@ impl<'a> DirEntry<'a> {
         })
     }
 
+    /// Parse a serialized reverse mapping from an entry to its parent into a DirEntry structure
+    pub fn from_parent_bytes(id: EntryId, bytes: &[u8]) -> Result<DirEntry> {
+        let reversed = DirEntry::new(id, bytes)?;
+
+        Ok(DirEntry {
+            id: id,
+            parent_id: reversed.id,
+            name: reversed.name,
+        })
+    }
+
     /// Serialize a DirEntry to its byte representation
     pub fn to_bytes(&self) -> Vec<u8> {
         let mut bytes = Vec::with_capacity(ENTRY_ID_SIZE + self.name.len());
@@ -57,4 +68,12 @@ impl<'a> DirEntry<'a> {
         bytes.extend_from_slice(self.name.as_bytes());
         bytes
     }
+
+    /// Serialize the reverse mapping from this entry to its parent (i.e. parent ID and name)
+    pub fn to_parent_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(ENTRY_ID_SIZE + self.name.len());
+        bytes.extend_from_slice(self.parent_id.as_ref());
+        bytes.extend_from_slice(self.name.as_bytes());
+        bytes
+    }
 }
@@ -5,12 +5,14 @@
 
 use adapter::Adapter;
 use byteorder::{ByteOrder, NativeEndian};
+use data_encoding::BASE64URL;
 use errors::*;
 use id::EntryId;
 use object::Object;
 use object::credential::Credential;
 use object::domain::Domain;
 use object::org_unit::OrgUnit;
+use object::policy::Policy;
 use object::root::Root;
 use object::system::System;
 use path::Path;
@@ -36,6 +38,9 @@ pub enum Class {
 
     /// Encrypted access credential
     Credential,
+
+    /// Authorization policy for the subtree containing it
+    Policy,
 }
 
 impl Class {
@@ -52,6 +57,7 @@ impl Class {
             2 => Class::OrgUnit,
             3 => Class::System,
             4 => Class::Credential,
+            5 => Class::Policy,
             other => {
                 let msg = format!("bad entry type: {}", other);
                 return Err(ErrorKind::ParseFailure(msg).into());
@@ -73,6 +79,8 @@ impl Class {
             Some(Class::System)
         } else if object.has_credential() {
             Some(Class::Credential)
+        } else if object.has_policy() {
+            Some(Class::Policy)
         } else {
             None
         }
@@ -83,19 +91,19 @@ impl Class {
         match *self {
             Class::Root => {
                 match *child {
-                    Class::Domain => true,
+                    Class::Domain | Class::Policy => true,
                     _ => false,
                 }
             }
             Class::Domain => {
                 match *child {
-                    Class::Domain | Class::OrgUnit => true,
+                    Class::Domain | Class::OrgUnit | Class::Policy => true,
                     _ => false,
                 }
             }
             Class::OrgUnit => {
                 match *child {
-                    Class::OrgUnit | Class::System | Class::Credential => true,
+                    Class::OrgUnit | Class::System | Class::Credential | Class::Policy => true,
                     _ => false,
                 }
             }
@@ -105,7 +113,7 @@ impl Class {
                     _ => false,
                 }
             }
-            Class::Credential => false,
+            Class::Credential | Class::Policy => false,
         }
     }
 
@@ -126,6 +134,7 @@ impl ToString for Class {
             Class::OrgUnit => "org_unit".to_string(),
             Class::System => "system".to_string(),
             Class::Credential => "credential".to_string(),
+            Class::Policy => "policy".to_string(),
         }
     }
 }
@@ -147,6 +156,9 @@ pub enum Entry {
 
     /// Encrypted access credential
     Credential(Credential),
+
+    /// Authorization policy for the subtree containing it
+    Policy(Policy),
 }
 
 impl Entry {
@@ -162,6 +174,67 @@ impl Entry {
         Ok(entry.deserialize()?)
     }
 
+    /// Obtain the class of this entry
+    pub fn class(&self) -> Class {
+        match *self {
+            Entry::Root(_) => Class::Root,
+            Entry::Domain(_) => Class::Domain,
+            Entry::OrgUnit(_) => Class::OrgUnit,
+            Entry::System(_) => Class::System,
+            Entry::Credential(_) => Class::Credential,
+            Entry::Policy(_) => Class::Policy,
+        }
+    }
+
+    /// Render the fields of this entry as name/value pairs for display. Secret values (even
+    /// encrypted ones) are redacted
+    pub fn fields(&self) -> Vec<(&'static str, String)> {
+        match *self {
+            Entry::Root(ref root) => vec![("digest_alg", format!("{:?}", root.get_digest_alg()))],
+            Entry::Domain(ref domain) => vec![("description", domain.get_description().to_owned())],
+            Entry::OrgUnit(ref org_unit) => {
+                vec![("description", org_unit.get_description().to_owned())]
+            }
+            Entry::System(ref system) => {
+                vec![
+                    ("username", system.get_username().to_owned()),
+                    ("description", system.get_description().to_owned()),
+                ]
+            }
+            Entry::Credential(ref credential) => {
+                vec![
+                    ("keyid", BASE64URL.encode(credential.get_keyid())),
+                    ("type", format!("{:?}", credential.get_credential_type())),
+                    ("alg", credential.get_credential_alg().to_owned()),
+                    ("sealing_alg", format!("{:?}", credential.get_sealing_alg())),
+                    (
+                        "encrypted_value",
+                        format!("[REDACTED] ({} bytes)", credential.get_encrypted_value().len()),
+                    ),
+                    ("salt", BASE64URL.encode(credential.get_salt())),
+                    ("public_key", BASE64URL.encode(credential.get_public_key())),
+                    ("not_before", credential.get_not_before().to_string()),
+                    ("not_after", credential.get_not_after().to_string()),
+                    ("description", credential.get_description().to_owned()),
+                ]
+            }
+            Entry::Policy(ref policy) => {
+                let actions: Vec<String> = policy
+                    .get_actions()
+                    .iter()
+                    .map(|action| format!("{:?}", action))
+                    .collect();
+
+                vec![
+                    ("description", policy.get_description().to_owned()),
+                    ("subjects", policy.get_subjects().join(", ")),
+                    ("actions", actions.join(", ")),
+                    ("threshold", policy.get_threshold().to_string()),
+                ]
+            }
+        }
+    }
+
     /// Convert an object to the `Entry` sum type
     pub fn from_object(object: &mut Object) -> Option<Entry> {
         if object.has_root() {
@@ -174,6 +247,8 @@ impl Entry {
             Some(Entry::System(object.take_system()))
         } else if object.has_credential() {
             Some(Entry::Credential(object.take_credential()))
+        } else if object.has_policy() {
+            Some(Entry::Policy(object.take_policy()))
         } else {
             None
         }
@@ -187,6 +262,7 @@ impl Entry {
             Entry::OrgUnit(ref entry) => entry.write_to_bytes()?,
             Entry::System(ref entry) => entry.write_to_bytes()?,
             Entry::Credential(ref entry) => entry.write_to_bytes()?,
+            Entry::Policy(ref entry) => entry.write_to_bytes()?,
         };
 
         Ok(result)
@@ -230,6 +306,7 @@ impl<'a> SerializedEntry<'a> {
             Class::Credential => {
                 Entry::Credential(protobuf::parse_from_bytes::<Credential>(self.data)?)
             }
+            Class::Policy => Entry::Policy(protobuf::parse_from_bytes::<Policy>(self.data)?),
         })
     }
 }
@@ -75,5 +75,40 @@ error_chain! {
             description("cryptographic operation failed")
             display("cryptographic operation failed: '{}'", t)
         }
+
+        TimestampInvalid(t: String) {
+            description("timestamp is out of the allowed range")
+            display("timestamp is out of the allowed range: '{}'", t)
+        }
+
+        SignatureInvalid(t: String) {
+            description("signature verification failed")
+            display("signature verification failed: '{}'", t)
+        }
+
+        UnauthorizedSigner(t: String) {
+            description("signer is not authorized to make this change")
+            display("signer is not authorized to make this change: '{}'", t)
+        }
+
+        VerificationFailure(t: String) {
+            description("database does not match its log")
+            display("database does not match its log: '{}'", t)
+        }
+
+        DatabaseLocked(t: String) {
+            description("database is in use by another process")
+            display("database is in use by another process: '{}'", t)
+        }
+
+        ReadOnly(t: String) {
+            description("database is read-only")
+            display("database is read-only: '{}'", t)
+        }
+
+        UpgradeRequired(t: String) {
+            description("database was created by an older version of ithos")
+            display("database was created by an older version of ithos: '{}'", t)
+        }
     }
 }
@@ -5,11 +5,14 @@
 
 #![deny(missing_docs, unsafe_code, warnings)]
 
+// Benchmarks require nightly Rust
+#![cfg_attr(feature = "bench", feature(test))]
+
 // For error-chain
 #![recursion_limit = "1024"]
 
 extern crate clap;
-use clap::{App, Arg, SubCommand};
+use clap::{App, Arg, ArgMatches, SubCommand};
 
 extern crate byteorder;
 extern crate chrono;
@@ -22,23 +25,30 @@ extern crate protobuf;
 extern crate ring;
 extern crate ring_pwhash as pwhash;
 extern crate rpassword;
-extern crate untrusted;
-
-#[cfg(test)]
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
 extern crate tempdir;
+#[cfg(all(test, feature = "bench"))]
+extern crate test;
+extern crate toml;
+extern crate untrusted;
 
 pub mod adapter;
 pub mod alg;
+pub mod audit;
 pub mod block;
 pub mod crypto;
 pub mod direntry;
 pub mod entry;
 pub mod errors;
 pub mod id;
+pub mod manifest;
 pub mod metadata;
 pub mod object;
 pub mod op;
 pub mod path;
+pub mod plan;
 pub mod server;
 pub mod setup;
 pub mod signature;
@@ -46,17 +56,134 @@ pub mod timestamp;
 pub mod transform;
 pub mod witness;
 
+use adapter::Adapter;
+#[cfg(feature = "file-adapter")]
+use adapter::file::FileAdapter;
+#[cfg(feature = "lmdb")]
+use adapter::lmdb::{LmdbAdapter, LmdbConfig};
+#[cfg(feature = "memory-adapter")]
+use adapter::memory::MemoryAdapter;
 use alg::{CipherSuite, PasswordAlg};
+use block::Block;
 use crypto::signing::KeyPair;
 use crypto::symmetric::AES256GCM_KEY_SIZE;
+use data_encoding::BASE64URL;
+use entry::Class;
 use errors::*;
+use id::BlockId;
+use manifest::{Manifest, State};
 use path::PathBuf;
+use plan::Change;
+use protobuf::Message;
 use ring::rand;
 use server::Server;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read, Write};
 use std::path::Path as StdPath;
+use timestamp::Timestamp;
 
 const DEFAULT_ADMIN_USERNAME: &'static str = "manager";
 
+#[cfg(feature = "lmdb")]
+const DEFAULT_BACKEND: &'static str = "lmdb";
+
+#[cfg(not(feature = "lmdb"))]
+const DEFAULT_BACKEND: &'static str = "file";
+
+/// Options for `ithos db` which configure the LMDB environment
+const LMDB_OPTIONS: [&'static str; 4] = ["map-size", "max-readers", "no-sync", "no-meta-sync"];
+
+/// Storage backends a database can be kept in
+#[derive(Copy, Clone, Debug)]
+enum Backend {
+    /// A directory containing an LMDB environment
+    #[cfg(feature = "lmdb")]
+    Lmdb,
+
+    /// A single append-only log file
+    #[cfg(feature = "file-adapter")]
+    File,
+}
+
+impl Backend {
+    /// Find the backend with the given name
+    fn named(name: &str) -> Backend {
+        match name {
+            #[cfg(feature = "lmdb")]
+            "lmdb" => Backend::Lmdb,
+            #[cfg(feature = "file-adapter")]
+            "file" => Backend::File,
+            _ => panic!("*** Error: unsupported backend: {name}", name = name),
+        }
+    }
+
+    /// Apply any backend-specific options given to `ithos db` before creating a database
+    #[cfg_attr(not(feature = "lmdb"), allow(unused_variables))]
+    fn configure(&self, database_path: &str, matches: &ArgMatches) {
+        match *self {
+            #[cfg(feature = "lmdb")]
+            Backend::Lmdb => configure_lmdb(database_path, matches),
+            #[cfg(feature = "file-adapter")]
+            Backend::File => {
+                if LMDB_OPTIONS.iter().any(|option| matches.is_present(option)) {
+                    panic!("*** Error: LMDB options can't be used with the file backend");
+                }
+            }
+        }
+    }
+
+    /// Determine which backend an existing database is kept in: file databases are a single
+    /// file, whereas LMDB databases are a directory
+    fn detect(database_path: &str) -> Backend {
+        if cfg!(feature = "file-adapter") && StdPath::new(database_path).is_file() {
+            Backend::named("file")
+        } else {
+            Backend::named(DEFAULT_BACKEND)
+        }
+    }
+
+    /// Determine which backend an existing database is kept in, for a command which will write
+    /// to it (or check that writes would succeed). Read-only databases are rejected up front
+    fn detect_writable(database_path: &str) -> Backend {
+        let backend = Backend::detect(database_path);
+
+        match backend {
+            #[cfg(feature = "lmdb")]
+            Backend::Lmdb => {
+                let config = LmdbConfig::load(StdPath::new(database_path)).unwrap_or_else(|err| {
+                    panic!("*** Error: couldn't load LMDB configuration: {err}", err = err);
+                });
+
+                if config.read_only {
+                    panic!(
+                        "*** Error: {path} is read-only (read_only is set in its {file})",
+                        path = database_path,
+                        file = adapter::lmdb::CONFIG_FILE
+                    );
+                }
+            }
+            #[cfg(feature = "file-adapter")]
+            Backend::File => (),
+        }
+
+        backend
+    }
+}
+
+/// Call a command function (which is generic over the storage adapter) with the adapter for the
+/// given backend
+macro_rules! with_backend {
+    ($backend:expr, $command:ident($($arg:expr),*)) => {
+        match $backend {
+            #[cfg(feature = "lmdb")]
+            Backend::Lmdb => $command::<LmdbAdapter>($($arg),*),
+            #[cfg(feature = "file-adapter")]
+            Backend::File => $command::<FileAdapter>($($arg),*),
+        }
+    };
+}
+
 fn main() {
     let version = "v0.1";
 
@@ -70,7 +197,14 @@ fn main() {
         )
         .arg_from_usage(
             "-u, --username=[NAME] 'Username of the admin user (default: manager)'",
-        );
+        )
+        .arg_from_usage(
+            "--backend=[BACKEND] 'Storage backend for the database: lmdb or file (default: lmdb)'",
+        )
+        .arg_from_usage("--map-size=[BYTES] 'Initial size of the LMDB memory map'")
+        .arg_from_usage("--max-readers=[COUNT] 'Maximum number of concurrent LMDB readers'")
+        .arg_from_usage("--no-sync 'Skip flushing LMDB commits to disk (faster, but unsafe)'")
+        .arg_from_usage("--no-meta-sync 'Skip flushing LMDB metadata to disk on commit'");
 
     let domain_add_command =
         SubCommand::with_name("domain")
@@ -89,13 +223,202 @@ fn main() {
                     .takes_value(true)
                     .required(true),
             )
-            .arg_from_usage("-u, --username=[NAME] 'Username to authenticate with'");
+            .arg_from_usage("-u, --username=[NAME] 'Username to authenticate with'")
+            .arg_from_usage("--description=[TEXT] 'Description of the new domain'")
+            .arg_from_usage("--comment=[TEXT] 'Comment explaining the change (stored in the log)'")
+            .arg_from_usage("--dry-run 'Check the domain could be added, without adding it'")
+            .arg_from_usage("--max-clock-skew=[SECONDS] 'Tolerated clock skew in seconds'");
+
+    let ou_command = SubCommand::with_name("ou")
+        .about("Manages organizational units")
+        .subcommand(entry_add_command("Adds a new organizational unit"));
+
+    let user_command = SubCommand::with_name("user")
+        .about("Manages system users")
+        .subcommand(entry_add_command("Adds a new system user"));
+
+    let credential_command = SubCommand::with_name("credential")
+        .about("Manages credentials")
+        .subcommand(entry_add_command(
+            "Generates a new signing key pair credential",
+        ));
+
+    let apply_command = SubCommand::with_name("apply")
+        .about("Applies the changes in a manifest file in a single block")
+        .arg(
+            Arg::with_name("manifest")
+                .help("Path to a TOML manifest describing the changes")
+                .index(1)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name("path")
+                .short("p")
+                .long("path")
+                .help("Path to the ithos database")
+                .takes_value(true)
+                .required(true),
+        )
+        .arg_from_usage("-u, --username=[NAME] 'Username to authenticate with'")
+        .arg_from_usage("--dry-run 'Check the changes could be applied, without applying them'")
+        .arg_from_usage("--max-clock-skew=[SECONDS] 'Tolerated clock skew in seconds'");
+
+    let plan_command = SubCommand::with_name("plan")
+        .about("Shows the changes needed to make the directory match a desired state file")
+        .arg(
+            Arg::with_name("state")
+                .help("Path to a TOML file describing the desired state of a subtree")
+                .index(1)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name("path")
+                .short("p")
+                .long("path")
+                .help("Path to the ithos database")
+                .takes_value(true)
+                .required(true),
+        )
+        .arg_from_usage("--body=[FILE] 'Write the unsigned block body to a file for review'");
+
+    let check_command = SubCommand::with_name("check")
+        .about("Checks whether a proposed (and possibly partially signed) block would be accepted")
+        .arg(
+            Arg::with_name("block")
+                .help("Path to a serialized block")
+                .index(1)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name("path")
+                .short("p")
+                .long("path")
+                .help("Path to the ithos database")
+                .takes_value(true)
+                .required(true),
+        )
+        .arg_from_usage("--max-clock-skew=[SECONDS] 'Tolerated clock skew in seconds'");
+
+    let log_command = SubCommand::with_name("log")
+        .about("Shows the blocks in the log, optionally only those affecting a path")
+        .arg(
+            Arg::with_name("entry")
+                .help("Only show changes to the entry at this path or beneath it")
+                .index(1),
+        )
+        .arg(
+            Arg::with_name("path")
+                .short("p")
+                .long("path")
+                .help("Path to the ithos database")
+                .takes_value(true)
+                .required(true),
+        );
+
+    let ls_command = SubCommand::with_name("ls")
+        .about("Lists the entries beneath the given path")
+        .arg(
+            Arg::with_name("entry")
+                .help("Path to the entry within the directory (default: /)")
+                .index(1),
+        )
+        .arg(
+            Arg::with_name("path")
+                .short("p")
+                .long("path")
+                .help("Path to the ithos database")
+                .takes_value(true)
+                .required(true),
+        )
+        .arg_from_usage("-R, --recursive 'List all entries in the subtree'");
+
+    let cat_command = SubCommand::with_name("cat")
+        .about("Shows the entry at the given path")
+        .arg(
+            Arg::with_name("entry")
+                .help("Path to the entry within the directory")
+                .index(1)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name("path")
+                .short("p")
+                .long("path")
+                .help("Path to the ithos database")
+                .takes_value(true)
+                .required(true),
+        );
+
+    // Historical views are replayed into an in-memory database
+    #[cfg(feature = "memory-adapter")]
+    let cat_command = cat_command.arg_from_usage(
+        "--at=[BLOCK|TIMESTAMP] 'Show the entry as of a block ID or Unix timestamp'",
+    );
 
-    let matches = App::new("ithos")
+    let export_command = SubCommand::with_name("export")
+        .about("Writes every block in the log to standard output")
+        .arg(
+            Arg::with_name("path")
+                .short("p")
+                .long("path")
+                .help("Path to the ithos database")
+                .takes_value(true)
+                .required(true),
+        );
+
+    let import_command = SubCommand::with_name("import")
+        .about("Creates a new ithos database by replaying an exported log")
+        .arg(
+            Arg::with_name("log")
+                .help("Path to a log written by 'ithos export'")
+                .index(1)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name("path")
+                .short("p")
+                .long("path")
+                .help("Path to the new ithos database")
+                .takes_value(true)
+                .required(true),
+        )
+        .arg_from_usage(
+            "--backend=[BACKEND] 'Storage backend to import into: lmdb or file (default: lmdb)'",
+        );
+
+    #[cfg(feature = "memory-adapter")]
+    let verify_command = SubCommand::with_name("verify")
+        .about("Verifies an ithos database by replaying its log from the initial block")
+        .arg(
+            Arg::with_name("path")
+                .short("p")
+                .long("path")
+                .help("Path to the ithos database")
+                .takes_value(true)
+                .required(true),
+        );
+
+    let app = App::new("ithos")
         .version(version)
         .subcommand(db_create_command)
         .subcommand(domain_add_command)
-        .get_matches();
+        .subcommand(ou_command)
+        .subcommand(user_command)
+        .subcommand(credential_command)
+        .subcommand(apply_command)
+        .subcommand(plan_command)
+        .subcommand(check_command)
+        .subcommand(log_command)
+        .subcommand(ls_command)
+        .subcommand(cat_command)
+        .subcommand(export_command)
+        .subcommand(import_command);
+
+    // Verification replays the log into a scratch in-memory database
+    #[cfg(feature = "memory-adapter")]
+    let app = app.subcommand(verify_command);
+
+    let matches = app.get_matches();
 
     if let Some(matches) = matches.subcommand_matches("db") {
         let db_path = matches.value_of("path").unwrap();
@@ -103,7 +426,10 @@ fn main() {
             DEFAULT_ADMIN_USERNAME,
         );
 
-        db_create(db_path, admin_username);
+        let backend = Backend::named(matches.value_of("backend").unwrap_or(DEFAULT_BACKEND));
+
+        backend.configure(db_path, matches);
+        with_backend!(backend, db_create(db_path, admin_username));
     } else if let Some(matches) = matches.subcommand_matches("domain") {
         let domain = matches.value_of("domain").unwrap();
         let db_path = matches.value_of("path").unwrap();
@@ -111,17 +437,143 @@ fn main() {
             DEFAULT_ADMIN_USERNAME,
         );
 
-        domain_add(db_path, username, domain);
+        with_backend!(
+            Backend::detect_writable(db_path),
+            domain_add(
+                db_path,
+                username,
+                domain,
+                matches.value_of("description"),
+                matches.value_of("comment"),
+                matches.is_present("dry-run"),
+                matches.value_of("max-clock-skew")
+            )
+        );
+    } else if let Some(matches) = matches.subcommand_matches("ou") {
+        if let Some(matches) = matches.subcommand_matches("add") {
+            let db_path = matches.value_of("path").unwrap();
+            with_backend!(Backend::detect_writable(db_path), entry_add(Class::OrgUnit, matches));
+        }
+    } else if let Some(matches) = matches.subcommand_matches("user") {
+        if let Some(matches) = matches.subcommand_matches("add") {
+            let db_path = matches.value_of("path").unwrap();
+            with_backend!(Backend::detect_writable(db_path), entry_add(Class::System, matches));
+        }
+    } else if let Some(matches) = matches.subcommand_matches("credential") {
+        if let Some(matches) = matches.subcommand_matches("add") {
+            let db_path = matches.value_of("path").unwrap();
+            with_backend!(Backend::detect_writable(db_path), entry_add(Class::Credential, matches));
+        }
+    } else if let Some(matches) = matches.subcommand_matches("apply") {
+        let db_path = matches.value_of("path").unwrap();
+        let username = matches.value_of("username").unwrap_or(
+            DEFAULT_ADMIN_USERNAME,
+        );
+
+        with_backend!(
+            Backend::detect_writable(db_path),
+            apply(
+                db_path,
+                username,
+                matches.value_of("manifest").unwrap(),
+                matches.is_present("dry-run"),
+                matches.value_of("max-clock-skew")
+            )
+        );
+    } else if let Some(matches) = matches.subcommand_matches("plan") {
+        let db_path = matches.value_of("path").unwrap();
+
+        with_backend!(
+            Backend::detect_writable(db_path),
+            plan(db_path, matches.value_of("state").unwrap(), matches.value_of("body"))
+        );
+    } else if let Some(matches) = matches.subcommand_matches("check") {
+        let db_path = matches.value_of("path").unwrap();
+        with_backend!(
+            Backend::detect_writable(db_path),
+            check(
+                db_path,
+                matches.value_of("block").unwrap(),
+                matches.value_of("max-clock-skew")
+            )
+        );
+    } else if let Some(matches) = matches.subcommand_matches("log") {
+        let db_path = matches.value_of("path").unwrap();
+        with_backend!(Backend::detect(db_path), log(db_path, matches.value_of("entry")));
+    } else if let Some(matches) = matches.subcommand_matches("ls") {
+        let db_path = matches.value_of("path").unwrap();
+
+        with_backend!(
+            Backend::detect(db_path),
+            ls(
+                db_path,
+                matches.value_of("entry").unwrap_or("/"),
+                matches.is_present("recursive")
+            )
+        );
+    } else if let Some(matches) = matches.subcommand_matches("cat") {
+        let db_path = matches.value_of("path").unwrap();
+
+        with_backend!(
+            Backend::detect(db_path),
+            cat(db_path, matches.value_of("entry").unwrap(), matches.value_of("at"))
+        );
+    } else if let Some(matches) = matches.subcommand_matches("export") {
+        let db_path = matches.value_of("path").unwrap();
+        with_backend!(Backend::detect(db_path), export(db_path));
+    } else if let Some(matches) = matches.subcommand_matches("import") {
+        let backend = Backend::named(matches.value_of("backend").unwrap_or(DEFAULT_BACKEND));
+
+        with_backend!(
+            backend,
+            import(matches.value_of("path").unwrap(), matches.value_of("log").unwrap())
+        );
+    }
+
+    #[cfg(feature = "memory-adapter")]
+    {
+        if let Some(matches) = matches.subcommand_matches("verify") {
+            let db_path = matches.value_of("path").unwrap();
+            with_backend!(Backend::detect(db_path), verify(db_path));
+        }
     }
 }
 
-fn db_create(database_path: &str, admin_username: &str) {
+/// Build an "add" subcommand for adding an entry at a given path
+fn entry_add_command<'a, 'b>(about: &'static str) -> App<'a, 'b> {
+    SubCommand::with_name("add")
+        .about(about)
+        .arg(
+            Arg::with_name("entry")
+                .help("Path to the new entry within the directory")
+                .index(1)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name("path")
+                .short("p")
+                .long("path")
+                .help("Path to the ithos database")
+                .takes_value(true)
+                .required(true),
+        )
+        .arg_from_usage("-u, --username=[NAME] 'Username to authenticate with'")
+        .arg_from_usage("--description=[TEXT] 'Description of the new entry'")
+        .arg_from_usage("--comment=[TEXT] 'Comment explaining the change (stored in the log)'")
+        .arg_from_usage("--dry-run 'Check the entry could be added, without adding it'")
+        .arg_from_usage("--max-clock-skew=[SECONDS] 'Tolerated clock skew in seconds'")
+}
+
+fn db_create<A>(database_path: &str, admin_username: &str)
+where
+    A: for<'a> Adapter<'a>,
+{
     println!("Creating database at: {path}", path = database_path);
 
     let rng = rand::SystemRandom::new();
     let admin_password = crypto::password::generate(&rng);
 
-    match Server::create_database(
+    match Server::<A>::create_database(
         StdPath::new(database_path),
         &rng,
         CipherSuite::Ed25519_AES256GCM_SHA256,
@@ -151,21 +603,347 @@ fn db_create(database_path: &str, admin_username: &str) {
     }
 }
 
-fn domain_add(database_path: &str, admin_username: &str, domain_name: &str) {
+/// Store the LMDB options given to `ithos db` in the new database's configuration file
+#[cfg(feature = "lmdb")]
+fn configure_lmdb(database_path: &str, matches: &ArgMatches) {
+    if !LMDB_OPTIONS.iter().any(|option| matches.is_present(option)) {
+        return;
+    }
+
+    let path = StdPath::new(database_path);
+    let mut config = LmdbConfig::load(path).unwrap_or_else(|err| {
+        panic!("*** Error: couldn't load LMDB configuration: {err}", err = err);
+    });
+
+    if let Some(map_size) = matches.value_of("map-size") {
+        config.map_size = map_size.parse().unwrap_or_else(|_| {
+            panic!("*** Error: invalid map size: {size}", size = map_size)
+        });
+    }
+
+    if let Some(max_readers) = matches.value_of("max-readers") {
+        config.max_readers = max_readers.parse().unwrap_or_else(|_| {
+            panic!("*** Error: invalid reader count: {count}", count = max_readers)
+        });
+    }
+
+    config.no_sync |= matches.is_present("no-sync");
+    config.no_meta_sync |= matches.is_present("no-meta-sync");
+
+    config.save(path).unwrap_or_else(|err| {
+        panic!("*** Error: couldn't save LMDB configuration: {err}", err = err);
+    });
+}
+
+fn domain_add<A>(
+    database_path: &str,
+    admin_username: &str,
+    domain_name: &str,
+    description: Option<&str>,
+    comment: Option<&str>,
+    dry_run: bool,
+    max_clock_skew: Option<&str>,
+) where
+    A: for<'a> Adapter<'a>,
+{
     println!(
-        "Creating domain '{domain}' in database at {path}",
+        "{action} domain '{domain}' in database at {path}",
+        action = if dry_run { "Checking" } else { "Creating" },
         path = database_path,
         domain = domain_name
     );
 
-    let server = Server::open_database(StdPath::new(database_path)).unwrap_or_else(|err| {
+    let server = open_database_for_writing::<A>(database_path, max_clock_skew);
+    let admin_keypair = unseal_admin_keypair(&server, admin_username);
+
+    let comment = comment.map(String::from).unwrap_or_else(|| {
+        format!("Creating {domain} domain", domain = domain_name)
+    });
+
+    match server.add_domain(
+        &admin_keypair,
+        domain_name,
+        description.map(String::from),
+        &comment,
+        dry_run,
+    ) {
+        Ok(_) if dry_run => println!("Dry run succeeded: no changes were made"),
+        Ok(_) => {
+            println!("Domain {domain} created!", domain = domain_name);
+        }
+        Err(err) => {
+            panic!(
+                "*** Error: couldn't create domain {domain}: #{err}",
+                domain = domain_name,
+                err = err
+            );
+        }
+    };
+}
+
+fn entry_add<A>(class: Class, matches: &ArgMatches)
+where
+    A: for<'a> Adapter<'a>,
+{
+    let database_path = matches.value_of("path").unwrap();
+    let entry_path = matches.value_of("entry").unwrap();
+    let admin_username = matches.value_of("username").unwrap_or(
+        DEFAULT_ADMIN_USERNAME,
+    );
+    let dry_run = matches.is_present("dry-run");
+
+    println!(
+        "{action} {class} '{entry}' in database at {path}",
+        action = if dry_run { "Checking" } else { "Creating" },
+        class = class.to_string(),
+        entry = entry_path,
+        path = database_path
+    );
+
+    let server = open_database_for_writing::<A>(database_path, matches.value_of("max-clock-skew"));
+    let admin_keypair = unseal_admin_keypair(&server, admin_username);
+    let path = parse_path(entry_path);
+    let description = matches.value_of("description").map(String::from);
+
+    let comment = matches.value_of("comment").map(String::from).unwrap_or_else(|| {
+        format!("Creating {class} {entry}", class = class.to_string(), entry = entry_path)
+    });
+
+    let rng = rand::SystemRandom::new();
+    let mut password = None;
+
+    let result = match class {
+        Class::OrgUnit => {
+            server.add_org_unit(&admin_keypair, path, description, &comment, dry_run)
+        }
+        Class::System => {
+            server.add_system_user(&admin_keypair, path, description, &comment, dry_run)
+        }
+        Class::Credential => {
+            let generated_password = crypto::password::generate(&rng);
+            let result = server.add_credential(
+                &admin_keypair,
+                &rng,
+                path,
+                &generated_password,
+                description,
+                &comment,
+                dry_run,
+            );
+
+            password = Some(generated_password);
+            result
+        }
+        _ => panic!("*** Error: can't add {} entries", class.to_string()),
+    };
+
+    if let Err(err) = result {
         panic!(
-            "*** Error: couldn't open database at {path}: {err}",
-            path = database_path,
+            "*** Error: couldn't create {entry}: {err}",
+            entry = entry_path,
+            err = err
+        );
+    }
+
+    if dry_run {
+        println!("Dry run succeeded: no changes were made");
+        return;
+    }
+
+    println!("{entry} created!", entry = entry_path);
+
+    if let Some(password) = password {
+        println!("\nBelow is the password for the new credential. Don't lose it!\n");
+        println!("{password}", password = password);
+    }
+}
+
+fn apply<A>(
+    database_path: &str,
+    admin_username: &str,
+    manifest_path: &str,
+    dry_run: bool,
+    max_clock_skew: Option<&str>,
+) where
+    A: for<'a> Adapter<'a>,
+{
+    let manifest = Manifest::load(StdPath::new(manifest_path)).unwrap_or_else(|err| {
+        panic!(
+            "*** Error: couldn't load manifest {manifest}: {err}",
+            manifest = manifest_path,
+            err = err
+        );
+    });
+
+    let ops = manifest.ops(Timestamp::now()).unwrap_or_else(|err| {
+        panic!("*** Error: invalid manifest: {err}", err = err);
+    });
+
+    println!(
+        "{action} {count} changes from {manifest} to database at {path}",
+        action = if dry_run { "Checking" } else { "Applying" },
+        count = ops.len(),
+        manifest = manifest_path,
+        path = database_path
+    );
+
+    let server = open_database_for_writing::<A>(database_path, max_clock_skew);
+    let admin_keypair = unseal_admin_keypair(&server, admin_username);
+
+    match server.apply_manifest(&admin_keypair, &manifest, dry_run) {
+        Ok(_) => {
+            for op in &ops {
+                println!("    {}", audit::describe(op));
+            }
+
+            if dry_run {
+                println!("Dry run succeeded: no changes were made");
+            }
+        }
+        Err(err) => {
+            panic!("*** Error: couldn't apply manifest: {err}", err = err);
+        }
+    }
+}
+
+fn plan<A>(database_path: &str, state_path: &str, body_path: Option<&str>)
+where
+    A: for<'a> Adapter<'a>,
+{
+    let state = State::load(StdPath::new(state_path)).unwrap_or_else(|err| {
+        panic!(
+            "*** Error: couldn't load desired state {state}: {err}",
+            state = state_path,
+            err = err
+        );
+    });
+
+    let server = open_database::<A>(database_path);
+    let plan = server.plan(&state).unwrap_or_else(|err| {
+        panic!("*** Error: couldn't plan changes: {err}", err = err);
+    });
+
+    if plan.changes.is_empty() {
+        println!("{root} already matches {state}", root = state.root, state = state_path);
+        return;
+    }
+
+    println!(
+        "Changes needed to make {root} match {state}:\n",
+        root = state.root,
+        state = state_path
+    );
+
+    for change in &plan.changes {
+        match *change {
+            Change::Add {
+                ref path,
+                class,
+                ref fields,
+            } => {
+                println!("+ {} ({})", path.as_path().to_string(), class.to_string());
+
+                for &(name, ref value) in fields {
+                    println!("      {}: {:?}", name, value);
+                }
+            }
+            Change::Update { ref path, ref fields } => {
+                println!("~ {}", path.as_path().to_string());
+
+                for field in fields {
+                    println!("      {}: {:?} -> {:?}", field.name, field.old, field.new);
+                }
+            }
+            Change::Delete { ref path, recursive } => {
+                let suffix = if recursive { " (recursive)" } else { "" };
+                println!("- {}{}", path.as_path().to_string(), suffix);
+            }
+        }
+    }
+
+    if let Some(body_path) = body_path {
+        let body = plan.block.get_body().write_to_bytes().unwrap();
+
+        File::create(body_path)
+            .and_then(|mut file| file.write_all(&body))
+            .unwrap_or_else(|err| {
+                panic!(
+                    "*** Error: couldn't write block body to {path}: {err}",
+                    path = body_path,
+                    err = err
+                );
+            });
+
+        println!("\nWrote unsigned block body to {path}", path = body_path);
+    }
+}
+
+fn check<A>(database_path: &str, block_path: &str, max_clock_skew: Option<&str>)
+where
+    A: for<'a> Adapter<'a>,
+{
+    let mut bytes = Vec::new();
+
+    File::open(block_path)
+        .and_then(|mut file| file.read_to_end(&mut bytes))
+        .unwrap_or_else(|err| {
+            panic!(
+                "*** Error: couldn't read {path}: {err}",
+                path = block_path,
+                err = err
+            );
+        });
+
+    let block = protobuf::parse_from_bytes::<Block>(&bytes).unwrap_or_else(|err| {
+        panic!(
+            "*** Error: {path} isn't a serialized block: {err}",
+            path = block_path,
             err = err
         );
     });
 
+    println!(
+        "Checking block {id} against database at {path}",
+        id = BASE64URL.encode(BlockId::of(&block).as_ref()),
+        path = database_path
+    );
+
+    let body = block.get_body();
+    println!("\n    {}\n", body.get_comment());
+
+    for op in body.get_ops() {
+        println!("    {}", audit::describe(op));
+    }
+
+    for signature in block.get_witness().get_signatures() {
+        println!("Signed by: {}", BASE64URL.encode(signature.get_public_key()));
+    }
+
+    println!();
+
+    let server = open_database_for_writing::<A>(database_path, max_clock_skew);
+
+    let missing = server.check_block(&block).unwrap_or_else(|err| {
+        println!("*** Error: block would be rejected: {err}", err = err);
+        std::process::exit(1);
+    });
+
+    if missing.is_empty() {
+        println!("Block would be accepted: no changes were made");
+    } else {
+        println!("Block is valid but still needs signatures:");
+
+        for authorization in missing {
+            println!("    {}", authorization);
+        }
+    }
+}
+
+/// Find the given administrator's signing key and unseal it with a password from the terminal
+fn unseal_admin_keypair<A>(server: &Server<A>, admin_username: &str) -> KeyPair
+where
+    A: for<'a> Adapter<'a>,
+{
     let mut keypair_path = PathBuf::new();
     keypair_path.push("global");
     keypair_path.push("users");
@@ -193,27 +971,252 @@ fn domain_add(database_path: &str, admin_username: &str, domain_name: &str) {
         &mut admin_symmetric_key,
     );
 
-    let admin_keypair = KeyPair::unseal_from_credential(&admin_credential, &admin_symmetric_key)
+    KeyPair::unseal_from_credential(&admin_credential, &admin_symmetric_key)
         .unwrap_or_else(|err| {
             panic!(
                 "*** Error: couldn't decrypt admin keypair: {} (wrong password?)",
                 err
             )
+        })
+}
+
+fn open_database<A>(database_path: &str) -> Server<A>
+where
+    A: for<'a> Adapter<'a>,
+{
+    Server::<A>::open_database(StdPath::new(database_path)).unwrap_or_else(|err| {
+        panic!(
+            "*** Error: couldn't open database at {path}: {err}",
+            path = database_path,
+            err = err
+        );
+    })
+}
+
+/// Open a database to add blocks to, allowing their timestamps to be ahead of our clock by the
+/// given number of seconds (if any) instead of the default
+fn open_database_for_writing<A>(database_path: &str, max_clock_skew: Option<&str>) -> Server<A>
+where
+    A: for<'a> Adapter<'a>,
+{
+    let mut server = open_database::<A>(database_path);
+
+    if let Some(seconds) = max_clock_skew {
+        server.set_max_clock_skew(seconds.parse().unwrap_or_else(|_| {
+            panic!("*** Error: invalid clock skew: {seconds}", seconds = seconds)
+        }));
+    }
+
+    server
+}
+
+/// Parse an absolute path to an entry within the directory
+fn parse_path(entry_path: &str) -> &path::Path {
+    path::Path::new(entry_path).unwrap_or_else(|| {
+        panic!("*** Error: entry paths must be absolute: {path}", path = entry_path)
+    })
+}
+
+/// Open a historical view of the directory given a block ID or Unix timestamp
+#[cfg(feature = "memory-adapter")]
+fn open_snapshot<A>(server: &Server<A>, at: &str) -> Server<MemoryAdapter>
+where
+    A: for<'a> Adapter<'a>,
+{
+    let snapshot = match at.parse::<u64>() {
+        Ok(secs) => server.at_time(Timestamp::at(secs)),
+        Err(_) => {
+            let block_id = BASE64URL
+                .decode(at.as_bytes())
+                .map_err(|err| Error::from(ErrorKind::ParseFailure(err.to_string())))
+                .and_then(|bytes| BlockId::from_bytes(&bytes));
+
+            block_id.and_then(|id| server.at_block(&id))
+        }
+    };
+
+    snapshot.unwrap_or_else(|err| {
+        panic!("*** Error: couldn't view directory at {at}: {err}", at = at, err = err);
+    })
+}
+
+fn log<A>(database_path: &str, entry_path: Option<&str>)
+where
+    A: for<'a> Adapter<'a>,
+{
+    let server = open_database::<A>(database_path);
+
+    let log = server.log(entry_path.map(parse_path)).unwrap_or_else(|err| {
+        panic!("*** Error: couldn't read log: {err}", err = err);
+    });
+
+    for entry in log {
+        println!("block {}", BASE64URL.encode(entry.block_id.as_ref()));
+        println!("Date:   {}", entry.timestamp.to_int());
+
+        for signer in &entry.signers {
+            match signer.credential_path {
+                Some(ref credential_path) => {
+                    println!(
+                        "Signer: {} ({})",
+                        credential_path.as_path().to_string(),
+                        BASE64URL.encode(&signer.public_key)
+                    )
+                }
+                None => println!("Signer: {}", BASE64URL.encode(&signer.public_key)),
+            }
+        }
+
+        println!("\n    {}\n", entry.comment);
+
+        for op in &entry.ops {
+            println!("    {}", audit::describe(op));
+        }
+
+        println!();
+    }
+}
+
+fn ls<A>(database_path: &str, entry_path: &str, recursive: bool)
+where
+    A: for<'a> Adapter<'a>,
+{
+    let server = open_database::<A>(database_path);
+    let path = parse_path(entry_path);
+
+    let result = if recursive {
+        server.walk(path)
+    } else {
+        server.list(path)
+    };
+
+    let entries = result.unwrap_or_else(|err| {
+        panic!("*** Error: couldn't list {path}: {err}", path = entry_path, err = err);
+    });
+
+    for (child_path, class) in entries {
+        println!("{:<12}{}", class.to_string(), child_path.as_path().to_string());
+    }
+}
+
+fn cat<A>(database_path: &str, entry_path: &str, at: Option<&str>)
+where
+    A: for<'a> Adapter<'a>,
+{
+    let server = open_database::<A>(database_path);
+
+    match at {
+        #[cfg(feature = "memory-adapter")]
+        Some(at) => print_entry(&open_snapshot(&server, at), entry_path),
+        _ => print_entry(&server, entry_path),
+    }
+}
+
+/// Print an entry along with its metadata
+fn print_entry<A>(server: &Server<A>, entry_path: &str)
+where
+    A: for<'a> Adapter<'a>,
+{
+    let path = parse_path(entry_path);
+
+    let (entry, metadata) = server
+        .find_entry(path)
+        .and_then(|entry| Ok((entry, server.find_metadata(path)?)))
+        .unwrap_or_else(|err| {
+            panic!("*** Error: couldn't find {path}: {err}", path = entry_path, err = err);
         });
 
-    let comment = format!("Creating {domain} domain", domain = domain_name);
+    print_field("path", entry_path);
+    print_field("class", entry.class().to_string());
+    print_field("version", metadata.get_version());
+    print_field(
+        "created",
+        format!(
+            "{} (block {})",
+            metadata.get_created_at(),
+            BASE64URL.encode(metadata.get_created_id())
+        ),
+    );
+    print_field(
+        "updated",
+        format!(
+            "{} (block {})",
+            metadata.get_updated_at(),
+            BASE64URL.encode(metadata.get_updated_id())
+        ),
+    );
+    println!();
+
+    for (name, value) in entry.fields() {
+        print_field(name, value);
+    }
+}
 
-    // TODO: description support
-    match server.add_domain(&admin_keypair, domain_name, None, &comment) {
-        Ok(_) => {
-            println!("Domain {domain} created!", domain = domain_name);
+/// Print a field of an entry (or its metadata) as an aligned "name: value" pair
+fn print_field<V: fmt::Display>(name: &str, value: V) {
+    println!("{:<16}{}", format!("{}:", name), value);
+}
+
+fn export<A>(database_path: &str)
+where
+    A: for<'a> Adapter<'a>,
+{
+    let server = open_database::<A>(database_path);
+    let stdout = io::stdout();
+
+    match server.export(&mut stdout.lock()) {
+        // Standard output holds the log, so report on standard error
+        Ok(count) => eprintln!("Exported {} blocks from {}", count, database_path),
+        Err(err) => {
+            eprintln!("*** Error: couldn't export log: {err}", err = err);
+            std::process::exit(1);
         }
+    }
+}
+
+fn import<A>(database_path: &str, log_path: &str)
+where
+    A: for<'a> Adapter<'a>,
+{
+    println!(
+        "Importing {log} into new database at {path}",
+        log = log_path,
+        path = database_path
+    );
+
+    let mut log = File::open(log_path).unwrap_or_else(|err| {
+        panic!(
+            "*** Error: couldn't open {log}: {err}",
+            log = log_path,
+            err = err
+        );
+    });
+
+    match Server::<A>::import(StdPath::new(database_path), &mut log) {
+        Ok(count) => println!("Imported and verified {} blocks", count),
         Err(err) => {
-            panic!(
-                "*** Error: couldn't create domain {domain}: #{err}",
-                domain = domain_name,
-                err = err
-            );
+            println!("*** Error: import failed: {err}", err = err);
+            std::process::exit(1);
         }
-    };
+    }
+}
+
+#[cfg(feature = "memory-adapter")]
+fn verify<A>(database_path: &str)
+where
+    A: for<'a> Adapter<'a>,
+{
+    println!("Verifying database at {path}", path = database_path);
+
+    let server = open_database::<A>(database_path);
+
+    match server.verify() {
+        Ok(count) => {
+            println!("Database is consistent with its log ({} blocks replayed)", count);
+        }
+        Err(err) => {
+            println!("*** Error: verification failed: {err}", err = err);
+            std::process::exit(1);
+        }
+    }
 }
@@ -0,0 +1,396 @@
+//! manifest.rs: Declarative descriptions of batches of changes to the directory
+//!
+//! A manifest is a TOML file listing entries to add, update and delete, along with a comment
+//! explaining the change. All of the ops it describes are placed in a single block, so they're
+//! signed once and applied (or rejected) atomically:
+//!
+//! ```toml
+//! comment = "Onboarding the platform team"
+//!
+//! [[add]]
+//! path = "/example.com/platform"
+//! class = "org_unit"
+//! description = "Platform team"
+//!
+//! [[update]]
+//! path = "/example.com/eng"
+//! class = "org_unit"
+//! description = "Product engineering"
+//! expected_version = 1
+//!
+//! [[delete]]
+//! path = "/example.com/ops"
+//! recursive = true
+//! ```
+//!
+//! Deletes are performed first, followed by updates, then adds (in the order they're listed),
+//! so an entry can be replaced by deleting and re-adding it in the same manifest.
+//!
+//! Alternatively, a desired state file lists every entry which should exist in a subtree (using
+//! `[[entry]]` tables in the same format as `[[add]]`), and the changes needed to reach that
+//! state are computed by diffing it against the directory.
+//!
+
+use data_encoding::BASE64URL;
+use entry::Class;
+use errors::*;
+use object::Object;
+use object::credential::{self, Credential};
+use object::domain::Domain;
+use object::org_unit::OrgUnit;
+use object::policy::{Action, Policy};
+use object::system::System;
+use op::{self, Op};
+use path::Path;
+use protobuf::RepeatedField;
+use serde::de::DeserializeOwned;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path as StdPath;
+use timestamp::Timestamp;
+use toml;
+
+/// Number of seconds credentials are valid for unless otherwise specified
+pub const DEFAULT_CREDENTIAL_LIFETIME: u64 = 31_557_600; // 1 year
+
+/// A batch of changes to be made to the directory in a single block
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    /// Comment explaining the changes, recorded in the block
+    pub comment: String,
+
+    /// Entries to add
+    #[serde(default)]
+    pub add: Vec<EntrySpec>,
+
+    /// Existing entries to replace
+    #[serde(default)]
+    pub update: Vec<EntrySpec>,
+
+    /// Entries to delete
+    #[serde(default)]
+    pub delete: Vec<DeleteSpec>,
+}
+
+/// Desired contents of an entry
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct EntrySpec {
+    /// Path to the entry
+    pub path: String,
+
+    /// Class of the entry: "domain", "org_unit", "system", "credential" or "policy"
+    pub class: String,
+
+    /// Description of the entry
+    pub description: Option<String>,
+
+    /// Credentials only: Base64url-encoded Ed25519 public key
+    pub public_key: Option<String>,
+
+    /// Credentials only: number of seconds the credential is valid for
+    pub lifetime: Option<u64>,
+
+    /// Policies only: paths of the credentials (or entries containing them) the policy applies to
+    #[serde(default)]
+    pub subjects: Vec<String>,
+
+    /// Policies only: actions granted by the policy, i.e. "add", "update" and/or "delete"
+    #[serde(default)]
+    pub actions: Vec<String>,
+
+    /// Policies only: number of distinct credentials under the subjects which must sign
+    #[serde(default)]
+    pub threshold: u32,
+
+    /// Updates only: version the entry is expected to be at
+    pub expected_version: Option<u64>,
+}
+
+/// An entry to be deleted
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct DeleteSpec {
+    /// Path to the entry
+    pub path: String,
+
+    /// Delete the entry's children along with it
+    #[serde(default)]
+    pub recursive: bool,
+
+    /// Version the entry is expected to be at
+    pub expected_version: Option<u64>,
+}
+
+/// Desired state of a subtree of the directory, from which the changes needed to reach it can
+/// be planned (see `Server::plan`)
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct State {
+    /// Comment explaining the changes, recorded in the block
+    pub comment: String,
+
+    /// Path to the subtree this state describes. Entries beneath it which aren't listed are deleted
+    pub root: String,
+
+    /// Entries which should exist beneath the root
+    #[serde(default, rename = "entry")]
+    pub entries: Vec<EntrySpec>,
+}
+
+impl Manifest {
+    /// Parse a manifest from a TOML string
+    pub fn parse(manifest: &str) -> Result<Manifest> {
+        parse_toml(manifest)
+    }
+
+    /// Load a manifest from a TOML file
+    pub fn load(path: &StdPath) -> Result<Manifest> {
+        parse_toml(&read_file(path)?)
+    }
+
+    /// Resolve the manifest into the ops to be placed in a block with the given timestamp
+    pub fn ops(&self, timestamp: Timestamp) -> Result<Vec<Op>> {
+        let mut ops = Vec::new();
+
+        for spec in &self.delete {
+            let mut op = Op::new();
+            op.set_optype(op::Type::DELETE);
+            op.set_path(parse_path(&spec.path)?.to_string());
+            op.set_recursive(spec.recursive);
+            op.set_expected_version(spec.expected_version.unwrap_or(0));
+            ops.push(op);
+        }
+
+        for spec in &self.update {
+            let mut op = Op::new();
+            op.set_optype(op::Type::UPDATE);
+            op.set_path(parse_path(&spec.path)?.to_string());
+            op.set_object(spec.to_object(timestamp)?);
+            op.set_expected_version(spec.expected_version.unwrap_or(0));
+            ops.push(op);
+        }
+
+        for spec in &self.add {
+            let mut op = Op::new();
+            op.set_optype(op::Type::ADD);
+            op.set_path(parse_path(&spec.path)?.to_string());
+            op.set_object(spec.to_object(timestamp)?);
+            ops.push(op);
+        }
+
+        Ok(ops)
+    }
+}
+
+impl State {
+    /// Parse a desired state from a TOML string
+    pub fn parse(state: &str) -> Result<State> {
+        parse_toml(state)
+    }
+
+    /// Load a desired state from a TOML file
+    pub fn load(path: &StdPath) -> Result<State> {
+        parse_toml(&read_file(path)?)
+    }
+}
+
+impl EntrySpec {
+    /// Parse the class of this entry
+    pub fn class(&self) -> Result<Class> {
+        match self.class.as_str() {
+            "domain" => Ok(Class::Domain),
+            "org_unit" => Ok(Class::OrgUnit),
+            "system" => Ok(Class::System),
+            "credential" => Ok(Class::Credential),
+            "policy" => Ok(Class::Policy),
+            other => {
+                let msg = format!("{}: unsupported class '{}'", self.path, other);
+                Err(ErrorKind::TypeInvalid(msg).into())
+            }
+        }
+    }
+
+    /// Build the object for this entry. Credentials become valid at the given time
+    pub fn to_object(&self, timestamp: Timestamp) -> Result<Object> {
+        let description = self.description.clone().unwrap_or_default();
+        let mut object = Object::new();
+
+        match self.class()? {
+            Class::Domain => {
+                let mut domain = Domain::new();
+                domain.set_description(description);
+                object.set_domain(domain);
+            }
+            Class::OrgUnit => {
+                let mut org_unit = OrgUnit::new();
+                org_unit.set_description(description);
+                object.set_org_unit(org_unit);
+            }
+            Class::System => {
+                let username = parse_path(&self.path)?.entry_name().unwrap_or("").to_owned();
+
+                let mut system = System::new();
+                system.set_username(username);
+                system.set_description(description);
+                object.set_system(system);
+            }
+            Class::Credential => {
+                let public_key = match self.public_key {
+                    Some(ref encoded) => {
+                        BASE64URL.decode(encoded.as_bytes()).map_err(|err| {
+                            let msg = format!("{}: bad public key: {}", self.path, err);
+                            Error::from(ErrorKind::ParseFailure(msg))
+                        })?
+                    }
+                    None => {
+                        let msg = format!("{}: credentials require a public_key", self.path);
+                        return Err(ErrorKind::ParseFailure(msg).into());
+                    }
+                };
+
+                let lifetime = self.lifetime.unwrap_or(DEFAULT_CREDENTIAL_LIFETIME);
+
+                let mut credential = Credential::new();
+                credential.set_credential_type(credential::Type::SIGNATURE_KEY_PAIR);
+                credential.set_public_key(public_key);
+                credential.set_not_before(timestamp.to_int());
+                credential.set_not_after(timestamp.extend(lifetime).to_int());
+                credential.set_description(description);
+                object.set_credential(credential);
+            }
+            Class::Policy => {
+                let actions = self.actions
+                    .iter()
+                    .map(|action| match action.as_str() {
+                        "add" => Ok(Action::ADD),
+                        "update" => Ok(Action::UPDATE),
+                        "delete" => Ok(Action::DELETE),
+                        other => {
+                            let msg = format!("{}: unknown action '{}'", self.path, other);
+                            Err(ErrorKind::ParseFailure(msg).into())
+                        }
+                    })
+                    .collect::<Result<Vec<Action>>>()?;
+
+                let mut policy = Policy::new();
+                policy.set_description(description);
+                policy.set_subjects(RepeatedField::from_vec(self.subjects.clone()));
+                policy.set_actions(actions);
+                policy.set_threshold(self.threshold);
+                object.set_policy(policy);
+            }
+            Class::Root => unreachable!(),
+        }
+
+        Ok(object)
+    }
+}
+
+/// Parse a TOML document
+fn parse_toml<T: DeserializeOwned>(document: &str) -> Result<T> {
+    toml::from_str(document).map_err(|err| ErrorKind::ParseFailure(err.to_string()).into())
+}
+
+/// Read the contents of a file
+fn read_file(path: &StdPath) -> Result<String> {
+    let mut contents = String::new();
+    File::open(path)?.read_to_string(&mut contents)?;
+    Ok(contents)
+}
+
+/// Parse an absolute path within the directory, as given in a manifest
+pub fn parse_path(path: &str) -> Result<&Path> {
+    Path::new(path).ok_or_else(|| ErrorKind::PathInvalid(path.to_owned()).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use manifest::Manifest;
+    use op;
+    use timestamp::Timestamp;
+
+    const EXAMPLE_MANIFEST: &str = r#"
+        comment = "Onboarding the platform team"
+
+        [[add]]
+        path = "/example.com/platform"
+        class = "org_unit"
+        description = "Platform team"
+
+        [[add]]
+        path = "/example.com/platform/deploy"
+        class = "system"
+
+        [[add]]
+        path = "/example.com/platform/admins"
+        class = "policy"
+        subjects = ["/example.com/platform/deploy"]
+        actions = ["add", "update"]
+
+        [[update]]
+        path = "/example.com/eng"
+        class = "org_unit"
+        expected_version = 1
+
+        [[delete]]
+        path = "/example.com/ops"
+        recursive = true
+    "#;
+
+    #[test]
+    fn manifest_ops() {
+        let manifest = Manifest::parse(EXAMPLE_MANIFEST).unwrap();
+        assert_eq!(manifest.comment, "Onboarding the platform team");
+
+        let ops = manifest.ops(Timestamp::now()).unwrap();
+        let summary: Vec<(op::Type, &str)> = ops.iter()
+            .map(|op| (op.get_optype(), op.get_path()))
+            .collect();
+
+        assert_eq!(
+            summary,
+            vec![
+                (op::Type::DELETE, "/example.com/ops"),
+                (op::Type::UPDATE, "/example.com/eng"),
+                (op::Type::ADD, "/example.com/platform"),
+                (op::Type::ADD, "/example.com/platform/deploy"),
+                (op::Type::ADD, "/example.com/platform/admins"),
+            ]
+        );
+
+        assert!(ops[0].get_recursive());
+        assert_eq!(ops[1].get_expected_version(), 1);
+        assert_eq!(ops[3].get_object().get_system().get_username(), "deploy");
+        assert_eq!(ops[4].get_object().get_policy().get_actions().len(), 2);
+    }
+
+    #[test]
+    fn invalid_manifests() {
+        let bad_class = "comment = \"x\"\n[[add]]\npath = \"/a\"\nclass = \"root\"\n";
+        let manifest = Manifest::parse(bad_class).unwrap();
+        assert!(manifest.ops(Timestamp::now()).is_err());
+
+        let relative_path = "comment = \"x\"\n[[delete]]\npath = \"a\"\n";
+        let manifest = Manifest::parse(relative_path).unwrap();
+        assert!(manifest.ops(Timestamp::now()).is_err());
+
+        assert!(Manifest::parse("[[add]]\npath = \"/a\"\n").is_err());
+    }
+
+    #[test]
+    fn misspelled_keys() {
+        let misspelled = [
+            "comment = \"x\"\ncommment = \"y\"\n",
+            "comment = \"x\"\n[[delete]]\npath = \"/a\"\nrecursve = true\n",
+            "comment = \"x\"\n[[update]]\npath = \"/a\"\nclass = \"org_unit\"\n\
+             expected_verison = 1\n",
+        ];
+
+        // Typos must not be silently ignored, lest the resulting ops do something unintended
+        for manifest in &misspelled {
+            assert!(Manifest::parse(manifest).is_err(), "accepted {:?}", manifest);
+        }
+    }
+}
@@ -29,6 +29,8 @@ pub struct Metadata {
     pub created_at: u64,
     pub updated_at: u64,
     pub version: u64,
+    pub deleted_id: ::std::vec::Vec<u8>,
+    pub deleted_at: u64,
     // special fields
     unknown_fields: ::protobuf::UnknownFields,
     cached_size: ::protobuf::CachedSize,
@@ -186,6 +188,63 @@ impl Metadata {
     fn mut_version_for_reflect(&mut self) -> &mut u64 {
         &mut self.version
     }
+
+    // bytes deleted_id = 6;
+
+    pub fn clear_deleted_id(&mut self) {
+        self.deleted_id.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_deleted_id(&mut self, v: ::std::vec::Vec<u8>) {
+        self.deleted_id = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_deleted_id(&mut self) -> &mut ::std::vec::Vec<u8> {
+        &mut self.deleted_id
+    }
+
+    // Take field
+    pub fn take_deleted_id(&mut self) -> ::std::vec::Vec<u8> {
+        ::std::mem::replace(&mut self.deleted_id, ::std::vec::Vec::new())
+    }
+
+    pub fn get_deleted_id(&self) -> &[u8] {
+        &self.deleted_id
+    }
+
+    fn get_deleted_id_for_reflect(&self) -> &::std::vec::Vec<u8> {
+        &self.deleted_id
+    }
+
+    fn mut_deleted_id_for_reflect(&mut self) -> &mut ::std::vec::Vec<u8> {
+        &mut self.deleted_id
+    }
+
+    // uint64 deleted_at = 7;
+
+    pub fn clear_deleted_at(&mut self) {
+        self.deleted_at = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_deleted_at(&mut self, v: u64) {
+        self.deleted_at = v;
+    }
+
+    pub fn get_deleted_at(&self) -> u64 {
+        self.deleted_at
+    }
+
+    fn get_deleted_at_for_reflect(&self) -> &u64 {
+        &self.deleted_at
+    }
+
+    fn mut_deleted_at_for_reflect(&mut self) -> &mut u64 {
+        &mut self.deleted_at
+    }
 }
 
 impl ::protobuf::Message for Metadata {
@@ -230,6 +289,18 @@ impl ::protobuf::Message for Metadata {
                     let tmp = is.read_uint64()?;
                     self.version = tmp;
                 }
+                6 => {
+                    ::protobuf::rt::read_singular_proto3_bytes_into(wire_type,
+                                                                    is,
+                                                                    &mut self.deleted_id)?;
+                }
+                7 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    };
+                    let tmp = is.read_uint64()?;
+                    self.deleted_at = tmp;
+                }
                 _ => {
                     ::protobuf::rt::read_unknown_or_skip_group(field_number,
                                                                wire_type,
@@ -266,6 +337,14 @@ impl ::protobuf::Message for Metadata {
                                                   self.version,
                                                   ::protobuf::wire_format::WireTypeVarint);
         };
+        if self.deleted_id != ::std::vec::Vec::new() {
+            my_size += ::protobuf::rt::bytes_size(6, &self.deleted_id);
+        };
+        if self.deleted_at != 0 {
+            my_size += ::protobuf::rt::value_size(7,
+                                                  self.deleted_at,
+                                                  ::protobuf::wire_format::WireTypeVarint);
+        };
         my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
         self.cached_size.set(my_size);
         my_size
@@ -289,6 +368,12 @@ impl ::protobuf::Message for Metadata {
         if self.version != 0 {
             os.write_uint64(5, self.version)?;
         };
+        if self.deleted_id != ::std::vec::Vec::new() {
+            os.write_bytes(6, &self.deleted_id)?;
+        };
+        if self.deleted_at != 0 {
+            os.write_uint64(7, self.deleted_at)?;
+        };
         os.write_unknown_fields(self.get_unknown_fields())?;
         ::std::result::Result::Ok(())
     }
@@ -354,6 +439,16 @@ impl ::protobuf::MessageStatic for Metadata {
                     Metadata::get_version_for_reflect,
                     Metadata::mut_version_for_reflect,
                 ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBytes>(
+                    "deleted_id",
+                    Metadata::get_deleted_id_for_reflect,
+                    Metadata::mut_deleted_id_for_reflect,
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                    "deleted_at",
+                    Metadata::get_deleted_at_for_reflect,
+                    Metadata::mut_deleted_at_for_reflect,
+                ));
                 ::protobuf::reflect::MessageDescriptor::new::<Metadata>(
                     "Metadata",
                     fields,
@@ -371,6 +466,8 @@ impl ::protobuf::Clear for Metadata {
         self.clear_created_at();
         self.clear_updated_at();
         self.clear_version();
+        self.clear_deleted_id();
+        self.clear_deleted_at();
         self.unknown_fields.clear();
     }
 }
@@ -389,7 +486,7 @@ impl ::protobuf::reflect::ProtobufValue for Metadata {
 
 static file_descriptor_proto_data: &'static [u8] =
     &[0x0a, 0x0e, 0x6d, 0x65, 0x74, 0x61, 0x64, 0x61, 0x74, 0x61, 0x2e, 0x70, 0x72, 0x6f, 0x74,
-      0x6f, 0x12, 0x05, 0x69, 0x74, 0x68, 0x6f, 0x73, 0x22, 0xa0, 0x01, 0x0a, 0x08, 0x4d, 0x65,
+      0x6f, 0x12, 0x05, 0x69, 0x74, 0x68, 0x6f, 0x73, 0x22, 0xde, 0x01, 0x0a, 0x08, 0x4d, 0x65,
       0x74, 0x61, 0x64, 0x61, 0x74, 0x61, 0x12, 0x1d, 0x0a, 0x0a, 0x63, 0x72, 0x65, 0x61, 0x74,
       0x65, 0x64, 0x5f, 0x69, 0x64, 0x18, 0x01, 0x20, 0x01, 0x28, 0x0c, 0x52, 0x09, 0x63, 0x72,
       0x65, 0x61, 0x74, 0x65, 0x64, 0x49, 0x64, 0x12, 0x1d, 0x0a, 0x0a, 0x75, 0x70, 0x64, 0x61,
@@ -400,37 +497,41 @@ static file_descriptor_proto_data: &'static [u8] =
       0x64, 0x61, 0x74, 0x65, 0x64, 0x5f, 0x61, 0x74, 0x18, 0x04, 0x20, 0x01, 0x28, 0x04, 0x52,
       0x09, 0x75, 0x70, 0x64, 0x61, 0x74, 0x65, 0x64, 0x41, 0x74, 0x12, 0x18, 0x0a, 0x07, 0x76,
       0x65, 0x72, 0x73, 0x69, 0x6f, 0x6e, 0x18, 0x05, 0x20, 0x01, 0x28, 0x04, 0x52, 0x07, 0x76,
-      0x65, 0x72, 0x73, 0x69, 0x6f, 0x6e, 0x4a, 0xbd, 0x03, 0x0a, 0x06, 0x12, 0x04, 0x00, 0x00,
-      0x0b, 0x01, 0x0a, 0x08, 0x0a, 0x01, 0x0c, 0x12, 0x03, 0x00, 0x00, 0x12, 0x0a, 0x08, 0x0a,
-      0x01, 0x02, 0x12, 0x03, 0x02, 0x08, 0x0d, 0x0a, 0x35, 0x0a, 0x02, 0x04, 0x00, 0x12, 0x04,
-      0x05, 0x00, 0x0b, 0x01, 0x1a, 0x29, 0x20, 0x4d, 0x65, 0x74, 0x61, 0x64, 0x61, 0x74, 0x61,
-      0x20, 0x61, 0x73, 0x73, 0x6f, 0x63, 0x69, 0x61, 0x74, 0x65, 0x64, 0x20, 0x77, 0x69, 0x74,
-      0x68, 0x20, 0x61, 0x6e, 0x20, 0x69, 0x74, 0x68, 0x6f, 0x73, 0x20, 0x65, 0x6e, 0x74, 0x72,
-      0x79, 0x0a, 0x0a, 0x0a, 0x0a, 0x03, 0x04, 0x00, 0x01, 0x12, 0x03, 0x05, 0x08, 0x10, 0x0a,
-      0x0b, 0x0a, 0x04, 0x04, 0x00, 0x02, 0x00, 0x12, 0x03, 0x06, 0x02, 0x18, 0x0a, 0x0d, 0x0a,
-      0x05, 0x04, 0x00, 0x02, 0x00, 0x04, 0x12, 0x04, 0x06, 0x02, 0x05, 0x12, 0x0a, 0x0c, 0x0a,
-      0x05, 0x04, 0x00, 0x02, 0x00, 0x05, 0x12, 0x03, 0x06, 0x02, 0x07, 0x0a, 0x0c, 0x0a, 0x05,
-      0x04, 0x00, 0x02, 0x00, 0x01, 0x12, 0x03, 0x06, 0x09, 0x13, 0x0a, 0x0c, 0x0a, 0x05, 0x04,
-      0x00, 0x02, 0x00, 0x03, 0x12, 0x03, 0x06, 0x16, 0x17, 0x0a, 0x0b, 0x0a, 0x04, 0x04, 0x00,
-      0x02, 0x01, 0x12, 0x03, 0x07, 0x02, 0x18, 0x0a, 0x0d, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x01,
-      0x04, 0x12, 0x04, 0x07, 0x02, 0x06, 0x18, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x01,
-      0x05, 0x12, 0x03, 0x07, 0x02, 0x07, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x01, 0x01,
-      0x12, 0x03, 0x07, 0x09, 0x13, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x01, 0x03, 0x12,
-      0x03, 0x07, 0x16, 0x17, 0x0a, 0x0b, 0x0a, 0x04, 0x04, 0x00, 0x02, 0x02, 0x12, 0x03, 0x08,
-      0x02, 0x18, 0x0a, 0x0d, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x02, 0x04, 0x12, 0x04, 0x08, 0x02,
-      0x07, 0x18, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x02, 0x05, 0x12, 0x03, 0x08, 0x02,
-      0x08, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x02, 0x01, 0x12, 0x03, 0x08, 0x09, 0x13,
-      0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x02, 0x03, 0x12, 0x03, 0x08, 0x16, 0x17, 0x0a,
-      0x0b, 0x0a, 0x04, 0x04, 0x00, 0x02, 0x03, 0x12, 0x03, 0x09, 0x02, 0x18, 0x0a, 0x0d, 0x0a,
-      0x05, 0x04, 0x00, 0x02, 0x03, 0x04, 0x12, 0x04, 0x09, 0x02, 0x08, 0x18, 0x0a, 0x0c, 0x0a,
-      0x05, 0x04, 0x00, 0x02, 0x03, 0x05, 0x12, 0x03, 0x09, 0x02, 0x08, 0x0a, 0x0c, 0x0a, 0x05,
-      0x04, 0x00, 0x02, 0x03, 0x01, 0x12, 0x03, 0x09, 0x09, 0x13, 0x0a, 0x0c, 0x0a, 0x05, 0x04,
-      0x00, 0x02, 0x03, 0x03, 0x12, 0x03, 0x09, 0x16, 0x17, 0x0a, 0x0b, 0x0a, 0x04, 0x04, 0x00,
-      0x02, 0x04, 0x12, 0x03, 0x0a, 0x02, 0x18, 0x0a, 0x0d, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x04,
-      0x04, 0x12, 0x04, 0x0a, 0x02, 0x09, 0x18, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x04,
-      0x05, 0x12, 0x03, 0x0a, 0x02, 0x08, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x04, 0x01,
-      0x12, 0x03, 0x0a, 0x09, 0x10, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x04, 0x03, 0x12,
-      0x03, 0x0a, 0x16, 0x17, 0x62, 0x06, 0x70, 0x72, 0x6f, 0x74, 0x6f, 0x33];
+      0x65, 0x72, 0x73, 0x69, 0x6f, 0x6e, 0x12, 0x1d, 0x0a, 0x0a, 0x64, 0x65, 0x6c, 0x65, 0x74,
+      0x65, 0x64, 0x5f, 0x69, 0x64, 0x18, 0x06, 0x20, 0x01, 0x28, 0x0c, 0x52, 0x09, 0x64, 0x65,
+      0x6c, 0x65, 0x74, 0x65, 0x64, 0x49, 0x64, 0x12, 0x1d, 0x0a, 0x0a, 0x64, 0x65, 0x6c, 0x65,
+      0x74, 0x65, 0x64, 0x5f, 0x61, 0x74, 0x18, 0x07, 0x20, 0x01, 0x28, 0x04, 0x52, 0x09, 0x64,
+      0x65, 0x6c, 0x65, 0x74, 0x65, 0x64, 0x41, 0x74, 0x4a, 0xbd, 0x03, 0x0a, 0x06, 0x12, 0x04,
+      0x00, 0x00, 0x0b, 0x01, 0x0a, 0x08, 0x0a, 0x01, 0x0c, 0x12, 0x03, 0x00, 0x00, 0x12, 0x0a,
+      0x08, 0x0a, 0x01, 0x02, 0x12, 0x03, 0x02, 0x08, 0x0d, 0x0a, 0x35, 0x0a, 0x02, 0x04, 0x00,
+      0x12, 0x04, 0x05, 0x00, 0x0b, 0x01, 0x1a, 0x29, 0x20, 0x4d, 0x65, 0x74, 0x61, 0x64, 0x61,
+      0x74, 0x61, 0x20, 0x61, 0x73, 0x73, 0x6f, 0x63, 0x69, 0x61, 0x74, 0x65, 0x64, 0x20, 0x77,
+      0x69, 0x74, 0x68, 0x20, 0x61, 0x6e, 0x20, 0x69, 0x74, 0x68, 0x6f, 0x73, 0x20, 0x65, 0x6e,
+      0x74, 0x72, 0x79, 0x0a, 0x0a, 0x0a, 0x0a, 0x03, 0x04, 0x00, 0x01, 0x12, 0x03, 0x05, 0x08,
+      0x10, 0x0a, 0x0b, 0x0a, 0x04, 0x04, 0x00, 0x02, 0x00, 0x12, 0x03, 0x06, 0x02, 0x18, 0x0a,
+      0x0d, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x00, 0x04, 0x12, 0x04, 0x06, 0x02, 0x05, 0x12, 0x0a,
+      0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x00, 0x05, 0x12, 0x03, 0x06, 0x02, 0x07, 0x0a, 0x0c,
+      0x0a, 0x05, 0x04, 0x00, 0x02, 0x00, 0x01, 0x12, 0x03, 0x06, 0x09, 0x13, 0x0a, 0x0c, 0x0a,
+      0x05, 0x04, 0x00, 0x02, 0x00, 0x03, 0x12, 0x03, 0x06, 0x16, 0x17, 0x0a, 0x0b, 0x0a, 0x04,
+      0x04, 0x00, 0x02, 0x01, 0x12, 0x03, 0x07, 0x02, 0x18, 0x0a, 0x0d, 0x0a, 0x05, 0x04, 0x00,
+      0x02, 0x01, 0x04, 0x12, 0x04, 0x07, 0x02, 0x06, 0x18, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00,
+      0x02, 0x01, 0x05, 0x12, 0x03, 0x07, 0x02, 0x07, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02,
+      0x01, 0x01, 0x12, 0x03, 0x07, 0x09, 0x13, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x01,
+      0x03, 0x12, 0x03, 0x07, 0x16, 0x17, 0x0a, 0x0b, 0x0a, 0x04, 0x04, 0x00, 0x02, 0x02, 0x12,
+      0x03, 0x08, 0x02, 0x18, 0x0a, 0x0d, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x02, 0x04, 0x12, 0x04,
+      0x08, 0x02, 0x07, 0x18, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x02, 0x05, 0x12, 0x03,
+      0x08, 0x02, 0x08, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x02, 0x01, 0x12, 0x03, 0x08,
+      0x09, 0x13, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x02, 0x03, 0x12, 0x03, 0x08, 0x16,
+      0x17, 0x0a, 0x0b, 0x0a, 0x04, 0x04, 0x00, 0x02, 0x03, 0x12, 0x03, 0x09, 0x02, 0x18, 0x0a,
+      0x0d, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x03, 0x04, 0x12, 0x04, 0x09, 0x02, 0x08, 0x18, 0x0a,
+      0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x03, 0x05, 0x12, 0x03, 0x09, 0x02, 0x08, 0x0a, 0x0c,
+      0x0a, 0x05, 0x04, 0x00, 0x02, 0x03, 0x01, 0x12, 0x03, 0x09, 0x09, 0x13, 0x0a, 0x0c, 0x0a,
+      0x05, 0x04, 0x00, 0x02, 0x03, 0x03, 0x12, 0x03, 0x09, 0x16, 0x17, 0x0a, 0x0b, 0x0a, 0x04,
+      0x04, 0x00, 0x02, 0x04, 0x12, 0x03, 0x0a, 0x02, 0x18, 0x0a, 0x0d, 0x0a, 0x05, 0x04, 0x00,
+      0x02, 0x04, 0x04, 0x12, 0x04, 0x0a, 0x02, 0x09, 0x18, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00,
+      0x02, 0x04, 0x05, 0x12, 0x03, 0x0a, 0x02, 0x08, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02,
+      0x04, 0x01, 0x12, 0x03, 0x0a, 0x09, 0x10, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x04,
+      0x03, 0x12, 0x03, 0x0a, 0x16, 0x17, 0x62, 0x06, 0x70, 0x72, 0x6f, 0x74, 0x6f, 0x33];
 
 static mut file_descriptor_proto_lazy: ::protobuf::lazy::Lazy<::protobuf::descriptor::FileDescriptorProto> = ::protobuf::lazy::Lazy {
     lock: ::protobuf::lazy::ONCE_INIT,
@@ -22,6 +22,7 @@
 pub mod credential;
 pub mod domain;
 pub mod org_unit;
+pub mod policy;
 pub mod root;
 pub mod system;
 
@@ -50,6 +51,7 @@ pub enum Object_oneof_value {
     org_unit(super::object::org_unit::OrgUnit),
     system(super::object::system::System),
     credential(super::object::credential::Credential),
+    policy(super::object::policy::Policy),
 }
 
 impl Object {
@@ -310,6 +312,55 @@ impl Object {
             _ => super::object::credential::Credential::default_instance(),
         }
     }
+
+    // .ithos.object.Policy policy = 6;
+
+    pub fn clear_policy(&mut self) {
+        self.value = ::std::option::Option::None;
+    }
+
+    pub fn has_policy(&self) -> bool {
+        match self.value {
+            ::std::option::Option::Some(Object_oneof_value::policy(..)) => true,
+            _ => false,
+        }
+    }
+
+    // Param is passed by value, moved
+    pub fn set_policy(&mut self, v: super::object::policy::Policy) {
+        self.value = ::std::option::Option::Some(Object_oneof_value::policy(v))
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_policy(&mut self) -> &mut super::object::policy::Policy {
+        if let ::std::option::Option::Some(Object_oneof_value::policy(_)) = self.value {
+        } else {
+            self.value = ::std::option::Option::Some(Object_oneof_value::policy(super::object::policy::Policy::new()));
+        }
+        match self.value {
+            ::std::option::Option::Some(Object_oneof_value::policy(ref mut v)) => v,
+            _ => panic!(),
+        }
+    }
+
+    // Take field
+    pub fn take_policy(&mut self) -> super::object::policy::Policy {
+        if self.has_policy() {
+            match self.value.take() {
+                ::std::option::Option::Some(Object_oneof_value::policy(v)) => v,
+                _ => panic!(),
+            }
+        } else {
+            super::object::policy::Policy::new()
+        }
+    }
+
+    pub fn get_policy(&self) -> &super::object::policy::Policy {
+        match self.value {
+            ::std::option::Option::Some(Object_oneof_value::policy(ref v)) => v,
+            _ => super::object::policy::Policy::default_instance(),
+        }
+    }
 }
 
 impl ::protobuf::Message for Object {
@@ -356,6 +407,13 @@ impl ::protobuf::Message for Object {
                     };
                     self.value = ::std::option::Option::Some(Object_oneof_value::credential(is.read_message()?));
                 }
+                6 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeLengthDelimited {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    };
+                    self.value =
+                        ::std::option::Option::Some(Object_oneof_value::policy(is.read_message()?));
+                }
                 _ => {
                     ::protobuf::rt::read_unknown_or_skip_group(field_number,
                                                                wire_type,
@@ -393,6 +451,10 @@ impl ::protobuf::Message for Object {
                     let len = v.compute_size();
                     my_size += 1 + ::protobuf::rt::compute_raw_varint32_size(len) + len;
                 }
+                &Object_oneof_value::policy(ref v) => {
+                    let len = v.compute_size();
+                    my_size += 1 + ::protobuf::rt::compute_raw_varint32_size(len) + len;
+                }
             };
         };
         my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
@@ -430,6 +492,11 @@ impl ::protobuf::Message for Object {
                     os.write_raw_varint32(v.get_cached_size())?;
                     v.write_to_with_cached_sizes(os)?;
                 }
+                &Object_oneof_value::policy(ref v) => {
+                    os.write_tag(6, ::protobuf::wire_format::WireTypeLengthDelimited)?;
+                    os.write_raw_varint32(v.get_cached_size())?;
+                    v.write_to_with_cached_sizes(os)?;
+                }
             };
         };
         os.write_unknown_fields(self.get_unknown_fields())?;
@@ -497,6 +564,11 @@ impl ::protobuf::MessageStatic for Object {
                     Object::has_credential,
                     Object::get_credential,
                 ));
+                fields.push(::protobuf::reflect::accessor::make_singular_message_accessor::<_, super::object::policy::Policy>(
+                    "policy",
+                    Object::has_policy,
+                    Object::get_policy,
+                ));
                 ::protobuf::reflect::MessageDescriptor::new::<Object>(
                     "Object",
                     fields,
@@ -514,6 +586,7 @@ impl ::protobuf::Clear for Object {
         self.clear_org_unit();
         self.clear_system();
         self.clear_credential();
+        self.clear_policy();
         self.unknown_fields.clear();
     }
 }
@@ -537,55 +610,60 @@ static file_descriptor_proto_data: &'static [u8] =
       0x6f, 0x1a, 0x13, 0x6f, 0x62, 0x6a, 0x65, 0x63, 0x74, 0x2f, 0x64, 0x6f, 0x6d, 0x61, 0x69,
       0x6e, 0x2e, 0x70, 0x72, 0x6f, 0x74, 0x6f, 0x1a, 0x15, 0x6f, 0x62, 0x6a, 0x65, 0x63, 0x74,
       0x2f, 0x6f, 0x72, 0x67, 0x5f, 0x75, 0x6e, 0x69, 0x74, 0x2e, 0x70, 0x72, 0x6f, 0x74, 0x6f,
-      0x1a, 0x11, 0x6f, 0x62, 0x6a, 0x65, 0x63, 0x74, 0x2f, 0x72, 0x6f, 0x6f, 0x74, 0x2e, 0x70,
-      0x72, 0x6f, 0x74, 0x6f, 0x1a, 0x13, 0x6f, 0x62, 0x6a, 0x65, 0x63, 0x74, 0x2f, 0x73, 0x79,
-      0x73, 0x74, 0x65, 0x6d, 0x2e, 0x70, 0x72, 0x6f, 0x74, 0x6f, 0x22, 0x8b, 0x02, 0x0a, 0x06,
-      0x4f, 0x62, 0x6a, 0x65, 0x63, 0x74, 0x12, 0x28, 0x0a, 0x04, 0x72, 0x6f, 0x6f, 0x74, 0x18,
-      0x01, 0x20, 0x01, 0x28, 0x0b, 0x32, 0x12, 0x2e, 0x69, 0x74, 0x68, 0x6f, 0x73, 0x2e, 0x6f,
-      0x62, 0x6a, 0x65, 0x63, 0x74, 0x2e, 0x52, 0x6f, 0x6f, 0x74, 0x48, 0x00, 0x52, 0x04, 0x72,
-      0x6f, 0x6f, 0x74, 0x12, 0x2e, 0x0a, 0x06, 0x64, 0x6f, 0x6d, 0x61, 0x69, 0x6e, 0x18, 0x02,
-      0x20, 0x01, 0x28, 0x0b, 0x32, 0x14, 0x2e, 0x69, 0x74, 0x68, 0x6f, 0x73, 0x2e, 0x6f, 0x62,
-      0x6a, 0x65, 0x63, 0x74, 0x2e, 0x44, 0x6f, 0x6d, 0x61, 0x69, 0x6e, 0x48, 0x00, 0x52, 0x06,
-      0x64, 0x6f, 0x6d, 0x61, 0x69, 0x6e, 0x12, 0x32, 0x0a, 0x08, 0x6f, 0x72, 0x67, 0x5f, 0x75,
-      0x6e, 0x69, 0x74, 0x18, 0x03, 0x20, 0x01, 0x28, 0x0b, 0x32, 0x15, 0x2e, 0x69, 0x74, 0x68,
-      0x6f, 0x73, 0x2e, 0x6f, 0x62, 0x6a, 0x65, 0x63, 0x74, 0x2e, 0x4f, 0x72, 0x67, 0x55, 0x6e,
-      0x69, 0x74, 0x48, 0x00, 0x52, 0x07, 0x6f, 0x72, 0x67, 0x55, 0x6e, 0x69, 0x74, 0x12, 0x2e,
-      0x0a, 0x06, 0x73, 0x79, 0x73, 0x74, 0x65, 0x6d, 0x18, 0x04, 0x20, 0x01, 0x28, 0x0b, 0x32,
-      0x14, 0x2e, 0x69, 0x74, 0x68, 0x6f, 0x73, 0x2e, 0x6f, 0x62, 0x6a, 0x65, 0x63, 0x74, 0x2e,
-      0x53, 0x79, 0x73, 0x74, 0x65, 0x6d, 0x48, 0x00, 0x52, 0x06, 0x73, 0x79, 0x73, 0x74, 0x65,
-      0x6d, 0x12, 0x3a, 0x0a, 0x0a, 0x63, 0x72, 0x65, 0x64, 0x65, 0x6e, 0x74, 0x69, 0x61, 0x6c,
-      0x18, 0x05, 0x20, 0x01, 0x28, 0x0b, 0x32, 0x18, 0x2e, 0x69, 0x74, 0x68, 0x6f, 0x73, 0x2e,
-      0x6f, 0x62, 0x6a, 0x65, 0x63, 0x74, 0x2e, 0x43, 0x72, 0x65, 0x64, 0x65, 0x6e, 0x74, 0x69,
-      0x61, 0x6c, 0x48, 0x00, 0x52, 0x0a, 0x63, 0x72, 0x65, 0x64, 0x65, 0x6e, 0x74, 0x69, 0x61,
-      0x6c, 0x42, 0x07, 0x0a, 0x05, 0x76, 0x61, 0x6c, 0x75, 0x65, 0x4a, 0x9a, 0x03, 0x0a, 0x06,
-      0x12, 0x04, 0x00, 0x00, 0x12, 0x01, 0x0a, 0x08, 0x0a, 0x01, 0x0c, 0x12, 0x03, 0x00, 0x00,
-      0x12, 0x0a, 0x08, 0x0a, 0x01, 0x02, 0x12, 0x03, 0x02, 0x08, 0x0d, 0x0a, 0x09, 0x0a, 0x02,
-      0x03, 0x00, 0x12, 0x03, 0x04, 0x07, 0x20, 0x0a, 0x09, 0x0a, 0x02, 0x03, 0x01, 0x12, 0x03,
-      0x05, 0x07, 0x1c, 0x0a, 0x09, 0x0a, 0x02, 0x03, 0x02, 0x12, 0x03, 0x06, 0x07, 0x1e, 0x0a,
-      0x09, 0x0a, 0x02, 0x03, 0x03, 0x12, 0x03, 0x07, 0x07, 0x1a, 0x0a, 0x09, 0x0a, 0x02, 0x03,
-      0x04, 0x12, 0x03, 0x08, 0x07, 0x1c, 0x0a, 0x0a, 0x0a, 0x02, 0x04, 0x00, 0x12, 0x04, 0x0a,
-      0x00, 0x12, 0x01, 0x0a, 0x0a, 0x0a, 0x03, 0x04, 0x00, 0x01, 0x12, 0x03, 0x0a, 0x08, 0x0e,
-      0x0a, 0x0c, 0x0a, 0x04, 0x04, 0x00, 0x08, 0x00, 0x12, 0x04, 0x0b, 0x02, 0x11, 0x03, 0x0a,
-      0x0c, 0x0a, 0x05, 0x04, 0x00, 0x08, 0x00, 0x01, 0x12, 0x03, 0x0b, 0x08, 0x0d, 0x0a, 0x0b,
-      0x0a, 0x04, 0x04, 0x00, 0x02, 0x00, 0x12, 0x03, 0x0c, 0x04, 0x2b, 0x0a, 0x0c, 0x0a, 0x05,
-      0x04, 0x00, 0x02, 0x00, 0x06, 0x12, 0x03, 0x0c, 0x04, 0x15, 0x0a, 0x0c, 0x0a, 0x05, 0x04,
-      0x00, 0x02, 0x00, 0x01, 0x12, 0x03, 0x0c, 0x1c, 0x20, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00,
-      0x02, 0x00, 0x03, 0x12, 0x03, 0x0c, 0x29, 0x2a, 0x0a, 0x0b, 0x0a, 0x04, 0x04, 0x00, 0x02,
-      0x01, 0x12, 0x03, 0x0d, 0x04, 0x2b, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x01, 0x06,
-      0x12, 0x03, 0x0d, 0x04, 0x17, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x01, 0x01, 0x12,
-      0x03, 0x0d, 0x1c, 0x22, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x01, 0x03, 0x12, 0x03,
-      0x0d, 0x29, 0x2a, 0x0a, 0x0b, 0x0a, 0x04, 0x04, 0x00, 0x02, 0x02, 0x12, 0x03, 0x0e, 0x04,
-      0x2b, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x02, 0x06, 0x12, 0x03, 0x0e, 0x04, 0x18,
-      0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x02, 0x01, 0x12, 0x03, 0x0e, 0x1c, 0x24, 0x0a,
-      0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x02, 0x03, 0x12, 0x03, 0x0e, 0x29, 0x2a, 0x0a, 0x0b,
-      0x0a, 0x04, 0x04, 0x00, 0x02, 0x03, 0x12, 0x03, 0x0f, 0x04, 0x2b, 0x0a, 0x0c, 0x0a, 0x05,
-      0x04, 0x00, 0x02, 0x03, 0x06, 0x12, 0x03, 0x0f, 0x04, 0x17, 0x0a, 0x0c, 0x0a, 0x05, 0x04,
-      0x00, 0x02, 0x03, 0x01, 0x12, 0x03, 0x0f, 0x1c, 0x22, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00,
-      0x02, 0x03, 0x03, 0x12, 0x03, 0x0f, 0x29, 0x2a, 0x0a, 0x0b, 0x0a, 0x04, 0x04, 0x00, 0x02,
-      0x04, 0x12, 0x03, 0x10, 0x04, 0x2b, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x04, 0x06,
-      0x12, 0x03, 0x10, 0x04, 0x1b, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x04, 0x01, 0x12,
-      0x03, 0x10, 0x1c, 0x26, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x04, 0x03, 0x12, 0x03,
-      0x10, 0x29, 0x2a, 0x62, 0x06, 0x70, 0x72, 0x6f, 0x74, 0x6f, 0x33];
+      0x1a, 0x13, 0x6f, 0x62, 0x6a, 0x65, 0x63, 0x74, 0x2f, 0x70, 0x6f, 0x6c, 0x69, 0x63, 0x79,
+      0x2e, 0x70, 0x72, 0x6f, 0x74, 0x6f, 0x1a, 0x11, 0x6f, 0x62, 0x6a, 0x65, 0x63, 0x74, 0x2f,
+      0x72, 0x6f, 0x6f, 0x74, 0x2e, 0x70, 0x72, 0x6f, 0x74, 0x6f, 0x1a, 0x13, 0x6f, 0x62, 0x6a,
+      0x65, 0x63, 0x74, 0x2f, 0x73, 0x79, 0x73, 0x74, 0x65, 0x6d, 0x2e, 0x70, 0x72, 0x6f, 0x74,
+      0x6f, 0x22, 0xbb, 0x02, 0x0a, 0x06, 0x4f, 0x62, 0x6a, 0x65, 0x63, 0x74, 0x12, 0x28, 0x0a,
+      0x04, 0x72, 0x6f, 0x6f, 0x74, 0x18, 0x01, 0x20, 0x01, 0x28, 0x0b, 0x32, 0x12, 0x2e, 0x69,
+      0x74, 0x68, 0x6f, 0x73, 0x2e, 0x6f, 0x62, 0x6a, 0x65, 0x63, 0x74, 0x2e, 0x52, 0x6f, 0x6f,
+      0x74, 0x48, 0x00, 0x52, 0x04, 0x72, 0x6f, 0x6f, 0x74, 0x12, 0x2e, 0x0a, 0x06, 0x64, 0x6f,
+      0x6d, 0x61, 0x69, 0x6e, 0x18, 0x02, 0x20, 0x01, 0x28, 0x0b, 0x32, 0x14, 0x2e, 0x69, 0x74,
+      0x68, 0x6f, 0x73, 0x2e, 0x6f, 0x62, 0x6a, 0x65, 0x63, 0x74, 0x2e, 0x44, 0x6f, 0x6d, 0x61,
+      0x69, 0x6e, 0x48, 0x00, 0x52, 0x06, 0x64, 0x6f, 0x6d, 0x61, 0x69, 0x6e, 0x12, 0x32, 0x0a,
+      0x08, 0x6f, 0x72, 0x67, 0x5f, 0x75, 0x6e, 0x69, 0x74, 0x18, 0x03, 0x20, 0x01, 0x28, 0x0b,
+      0x32, 0x15, 0x2e, 0x69, 0x74, 0x68, 0x6f, 0x73, 0x2e, 0x6f, 0x62, 0x6a, 0x65, 0x63, 0x74,
+      0x2e, 0x4f, 0x72, 0x67, 0x55, 0x6e, 0x69, 0x74, 0x48, 0x00, 0x52, 0x07, 0x6f, 0x72, 0x67,
+      0x55, 0x6e, 0x69, 0x74, 0x12, 0x2e, 0x0a, 0x06, 0x73, 0x79, 0x73, 0x74, 0x65, 0x6d, 0x18,
+      0x04, 0x20, 0x01, 0x28, 0x0b, 0x32, 0x14, 0x2e, 0x69, 0x74, 0x68, 0x6f, 0x73, 0x2e, 0x6f,
+      0x62, 0x6a, 0x65, 0x63, 0x74, 0x2e, 0x53, 0x79, 0x73, 0x74, 0x65, 0x6d, 0x48, 0x00, 0x52,
+      0x06, 0x73, 0x79, 0x73, 0x74, 0x65, 0x6d, 0x12, 0x3a, 0x0a, 0x0a, 0x63, 0x72, 0x65, 0x64,
+      0x65, 0x6e, 0x74, 0x69, 0x61, 0x6c, 0x18, 0x05, 0x20, 0x01, 0x28, 0x0b, 0x32, 0x18, 0x2e,
+      0x69, 0x74, 0x68, 0x6f, 0x73, 0x2e, 0x6f, 0x62, 0x6a, 0x65, 0x63, 0x74, 0x2e, 0x43, 0x72,
+      0x65, 0x64, 0x65, 0x6e, 0x74, 0x69, 0x61, 0x6c, 0x48, 0x00, 0x52, 0x0a, 0x63, 0x72, 0x65,
+      0x64, 0x65, 0x6e, 0x74, 0x69, 0x61, 0x6c, 0x12, 0x2e, 0x0a, 0x06, 0x70, 0x6f, 0x6c, 0x69,
+      0x63, 0x79, 0x18, 0x06, 0x20, 0x01, 0x28, 0x0b, 0x32, 0x14, 0x2e, 0x69, 0x74, 0x68, 0x6f,
+      0x73, 0x2e, 0x6f, 0x62, 0x6a, 0x65, 0x63, 0x74, 0x2e, 0x50, 0x6f, 0x6c, 0x69, 0x63, 0x79,
+      0x48, 0x00, 0x52, 0x06, 0x70, 0x6f, 0x6c, 0x69, 0x63, 0x79, 0x42, 0x07, 0x0a, 0x05, 0x76,
+      0x61, 0x6c, 0x75, 0x65, 0x4a, 0x9a, 0x03, 0x0a, 0x06, 0x12, 0x04, 0x00, 0x00, 0x12, 0x01,
+      0x0a, 0x08, 0x0a, 0x01, 0x0c, 0x12, 0x03, 0x00, 0x00, 0x12, 0x0a, 0x08, 0x0a, 0x01, 0x02,
+      0x12, 0x03, 0x02, 0x08, 0x0d, 0x0a, 0x09, 0x0a, 0x02, 0x03, 0x00, 0x12, 0x03, 0x04, 0x07,
+      0x20, 0x0a, 0x09, 0x0a, 0x02, 0x03, 0x01, 0x12, 0x03, 0x05, 0x07, 0x1c, 0x0a, 0x09, 0x0a,
+      0x02, 0x03, 0x02, 0x12, 0x03, 0x06, 0x07, 0x1e, 0x0a, 0x09, 0x0a, 0x02, 0x03, 0x03, 0x12,
+      0x03, 0x07, 0x07, 0x1a, 0x0a, 0x09, 0x0a, 0x02, 0x03, 0x04, 0x12, 0x03, 0x08, 0x07, 0x1c,
+      0x0a, 0x0a, 0x0a, 0x02, 0x04, 0x00, 0x12, 0x04, 0x0a, 0x00, 0x12, 0x01, 0x0a, 0x0a, 0x0a,
+      0x03, 0x04, 0x00, 0x01, 0x12, 0x03, 0x0a, 0x08, 0x0e, 0x0a, 0x0c, 0x0a, 0x04, 0x04, 0x00,
+      0x08, 0x00, 0x12, 0x04, 0x0b, 0x02, 0x11, 0x03, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x08,
+      0x00, 0x01, 0x12, 0x03, 0x0b, 0x08, 0x0d, 0x0a, 0x0b, 0x0a, 0x04, 0x04, 0x00, 0x02, 0x00,
+      0x12, 0x03, 0x0c, 0x04, 0x2b, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x00, 0x06, 0x12,
+      0x03, 0x0c, 0x04, 0x15, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x00, 0x01, 0x12, 0x03,
+      0x0c, 0x1c, 0x20, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x00, 0x03, 0x12, 0x03, 0x0c,
+      0x29, 0x2a, 0x0a, 0x0b, 0x0a, 0x04, 0x04, 0x00, 0x02, 0x01, 0x12, 0x03, 0x0d, 0x04, 0x2b,
+      0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x01, 0x06, 0x12, 0x03, 0x0d, 0x04, 0x17, 0x0a,
+      0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x01, 0x01, 0x12, 0x03, 0x0d, 0x1c, 0x22, 0x0a, 0x0c,
+      0x0a, 0x05, 0x04, 0x00, 0x02, 0x01, 0x03, 0x12, 0x03, 0x0d, 0x29, 0x2a, 0x0a, 0x0b, 0x0a,
+      0x04, 0x04, 0x00, 0x02, 0x02, 0x12, 0x03, 0x0e, 0x04, 0x2b, 0x0a, 0x0c, 0x0a, 0x05, 0x04,
+      0x00, 0x02, 0x02, 0x06, 0x12, 0x03, 0x0e, 0x04, 0x18, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00,
+      0x02, 0x02, 0x01, 0x12, 0x03, 0x0e, 0x1c, 0x24, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02,
+      0x02, 0x03, 0x12, 0x03, 0x0e, 0x29, 0x2a, 0x0a, 0x0b, 0x0a, 0x04, 0x04, 0x00, 0x02, 0x03,
+      0x12, 0x03, 0x0f, 0x04, 0x2b, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x03, 0x06, 0x12,
+      0x03, 0x0f, 0x04, 0x17, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x03, 0x01, 0x12, 0x03,
+      0x0f, 0x1c, 0x22, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x03, 0x03, 0x12, 0x03, 0x0f,
+      0x29, 0x2a, 0x0a, 0x0b, 0x0a, 0x04, 0x04, 0x00, 0x02, 0x04, 0x12, 0x03, 0x10, 0x04, 0x2b,
+      0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x04, 0x06, 0x12, 0x03, 0x10, 0x04, 0x1b, 0x0a,
+      0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x04, 0x01, 0x12, 0x03, 0x10, 0x1c, 0x26, 0x0a, 0x0c,
+      0x0a, 0x05, 0x04, 0x00, 0x02, 0x04, 0x03, 0x12, 0x03, 0x10, 0x29, 0x2a, 0x62, 0x06, 0x70,
+      0x72, 0x6f, 0x74, 0x6f, 0x33];
 
 static mut file_descriptor_proto_lazy: ::protobuf::lazy::Lazy<::protobuf::descriptor::FileDescriptorProto> = ::protobuf::lazy::Lazy {
     lock: ::protobuf::lazy::ONCE_INIT,
@@ -613,6 +691,8 @@ impl ObjectHash for Object {
             objecthash_struct!(hasher, "system" => self.get_system());
         } else if self.has_credential() {
             objecthash_struct!(hasher, "credential" => self.get_credential());
+        } else if self.has_policy() {
+            objecthash_struct!(hasher, "policy" => self.get_policy());
         }
     }
 }
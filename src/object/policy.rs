@@ -0,0 +1,451 @@
+// This file is generated. Do not edit
+// @generated
+
+// https://github.com/Manishearth/rust-clippy/issues/702
+#![allow(unknown_lints)]
+#![allow(clippy)]
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+
+#![allow(box_pointers)]
+#![allow(dead_code)]
+#![allow(non_camel_case_types)]
+#![allow(non_snake_case)]
+#![allow(non_upper_case_globals)]
+#![allow(trivial_casts)]
+#![allow(unsafe_code)]
+#![allow(unused_imports)]
+#![allow(unused_results)]
+
+// TODO: Hand edited! Figure out a better solution for objecthash support
+
+use objecthash::{self, ObjectHash, ObjectHasher};
+use protobuf::Message as Message_imported_for_functions;
+use protobuf::ProtobufEnum as ProtobufEnum_imported_for_functions;
+
+#[derive(PartialEq,Clone,Default)]
+pub struct Policy {
+    // message fields
+    pub description: ::std::string::String,
+    subjects: ::protobuf::RepeatedField<::std::string::String>,
+    actions: ::std::vec::Vec<Action>,
+    pub threshold: u32,
+    // special fields
+    unknown_fields: ::protobuf::UnknownFields,
+    cached_size: ::protobuf::CachedSize,
+}
+
+// see codegen.rs for the explanation why impl Sync explicitly
+unsafe impl ::std::marker::Sync for Policy {}
+
+impl Policy {
+    pub fn new() -> Policy {
+        ::std::default::Default::default()
+    }
+
+    pub fn default_instance() -> &'static Policy {
+        static mut instance: ::protobuf::lazy::Lazy<Policy> = ::protobuf::lazy::Lazy {
+            lock: ::protobuf::lazy::ONCE_INIT,
+            ptr: 0 as *const Policy,
+        };
+        unsafe { instance.get(Policy::new) }
+    }
+
+    // string description = 1;
+
+    pub fn clear_description(&mut self) {
+        self.description.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_description(&mut self, v: ::std::string::String) {
+        self.description = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_description(&mut self) -> &mut ::std::string::String {
+        &mut self.description
+    }
+
+    // Take field
+    pub fn take_description(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.description, ::std::string::String::new())
+    }
+
+    pub fn get_description(&self) -> &str {
+        &self.description
+    }
+
+    fn get_description_for_reflect(&self) -> &::std::string::String {
+        &self.description
+    }
+
+    fn mut_description_for_reflect(&mut self) -> &mut ::std::string::String {
+        &mut self.description
+    }
+
+    // repeated string subjects = 2;
+
+    pub fn clear_subjects(&mut self) {
+        self.subjects.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_subjects(&mut self, v: ::protobuf::RepeatedField<::std::string::String>) {
+        self.subjects = v;
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_subjects(&mut self) -> &mut ::protobuf::RepeatedField<::std::string::String> {
+        &mut self.subjects
+    }
+
+    // Take field
+    pub fn take_subjects(&mut self) -> ::protobuf::RepeatedField<::std::string::String> {
+        ::std::mem::replace(&mut self.subjects, ::protobuf::RepeatedField::new())
+    }
+
+    pub fn get_subjects(&self) -> &[::std::string::String] {
+        &self.subjects
+    }
+
+    fn get_subjects_for_reflect(&self) -> &::protobuf::RepeatedField<::std::string::String> {
+        &self.subjects
+    }
+
+    fn mut_subjects_for_reflect(&mut self)
+                                -> &mut ::protobuf::RepeatedField<::std::string::String> {
+        &mut self.subjects
+    }
+
+    // repeated .ithos.object.Action actions = 3;
+
+    pub fn clear_actions(&mut self) {
+        self.actions.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_actions(&mut self, v: ::std::vec::Vec<Action>) {
+        self.actions = v;
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_actions(&mut self) -> &mut ::std::vec::Vec<Action> {
+        &mut self.actions
+    }
+
+    // Take field
+    pub fn take_actions(&mut self) -> ::std::vec::Vec<Action> {
+        ::std::mem::replace(&mut self.actions, ::std::vec::Vec::new())
+    }
+
+    pub fn get_actions(&self) -> &[Action] {
+        &self.actions
+    }
+
+    fn get_actions_for_reflect(&self) -> &::std::vec::Vec<Action> {
+        &self.actions
+    }
+
+    fn mut_actions_for_reflect(&mut self) -> &mut ::std::vec::Vec<Action> {
+        &mut self.actions
+    }
+
+    // uint32 threshold = 4;
+
+    pub fn clear_threshold(&mut self) {
+        self.threshold = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_threshold(&mut self, v: u32) {
+        self.threshold = v;
+    }
+
+    pub fn get_threshold(&self) -> u32 {
+        self.threshold
+    }
+
+    fn get_threshold_for_reflect(&self) -> &u32 {
+        &self.threshold
+    }
+
+    fn mut_threshold_for_reflect(&mut self) -> &mut u32 {
+        &mut self.threshold
+    }
+}
+
+impl ::protobuf::Message for Policy {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self,
+                  is: &mut ::protobuf::CodedInputStream)
+                  -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type,
+                                                                     is,
+                                                                     &mut self.description)?;
+                }
+                2 => {
+                    ::protobuf::rt::read_repeated_string_into(wire_type, is, &mut self.subjects)?;
+                }
+                3 => {
+                    ::protobuf::rt::read_repeated_enum_into(wire_type, is, &mut self.actions)?;
+                }
+                4 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    };
+                    let tmp = is.read_uint32()?;
+                    self.threshold = tmp;
+                }
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number,
+                                                               wire_type,
+                                                               is,
+                                                               self.mut_unknown_fields())?;
+                }
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if self.description != ::std::string::String::new() {
+            my_size += ::protobuf::rt::string_size(1, &self.description);
+        };
+        for value in &self.subjects {
+            my_size += ::protobuf::rt::string_size(2, &value);
+        };
+        my_size += ::protobuf::rt::vec_packed_enum_size(3, &self.actions);
+        if self.threshold != 0 {
+            my_size += ::protobuf::rt::value_size(4,
+                                                  self.threshold,
+                                                  ::protobuf::wire_format::WireTypeVarint);
+        };
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self,
+                                  os: &mut ::protobuf::CodedOutputStream)
+                                  -> ::protobuf::ProtobufResult<()> {
+        if self.description != ::std::string::String::new() {
+            os.write_string(1, &self.description)?;
+        };
+        for v in &self.subjects {
+            os.write_string(2, &v)?;
+        };
+        if !self.actions.is_empty() {
+            os.write_tag(3, ::protobuf::wire_format::WireTypeLengthDelimited)?;
+            // TODO: Data size is computed again, it should be cached
+            os.write_raw_varint32(::protobuf::rt::vec_packed_enum_data_size(&self.actions))?;
+            for v in &self.actions {
+                os.write_enum_no_tag(v.value())?;
+            };
+        };
+        if self.threshold != 0 {
+            os.write_uint32(4, self.threshold)?;
+        };
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &::std::any::Any {
+        self as &::std::any::Any
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        ::protobuf::MessageStatic::descriptor_static(None::<Self>)
+    }
+}
+
+impl ::protobuf::MessageStatic for Policy {
+    fn new() -> Policy {
+        Policy::new()
+    }
+
+    fn descriptor_static(_: ::std::option::Option<Policy>)
+                         -> &'static ::protobuf::reflect::MessageDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> =
+            ::protobuf::lazy::Lazy {
+                lock: ::protobuf::lazy::ONCE_INIT,
+                ptr: 0 as *const ::protobuf::reflect::MessageDescriptor,
+            };
+        unsafe {
+            descriptor.get(|| {
+                let mut fields = ::std::vec::Vec::new();
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "description",
+                    Policy::get_description_for_reflect,
+                    Policy::mut_description_for_reflect,
+                ));
+                fields.push(::protobuf::reflect::accessor::make_repeated_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "subjects",
+                    Policy::get_subjects_for_reflect,
+                    Policy::mut_subjects_for_reflect,
+                ));
+                fields.push(::protobuf::reflect::accessor::make_vec_accessor::<_, ::protobuf::types::ProtobufTypeEnum<Action>>(
+                    "actions",
+                    Policy::get_actions_for_reflect,
+                    Policy::mut_actions_for_reflect,
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint32>(
+                    "threshold",
+                    Policy::get_threshold_for_reflect,
+                    Policy::mut_threshold_for_reflect,
+                ));
+                ::protobuf::reflect::MessageDescriptor::new::<Policy>(
+                    "Policy",
+                    fields,
+                    file_descriptor_proto()
+                )
+            })
+        }
+    }
+}
+
+impl ::protobuf::Clear for Policy {
+    fn clear(&mut self) {
+        self.clear_description();
+        self.clear_subjects();
+        self.clear_actions();
+        self.clear_threshold();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for Policy {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for Policy {
+    fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
+        ::protobuf::reflect::ProtobufValueRef::Message(self)
+    }
+}
+
+#[derive(Clone,PartialEq,Eq,Debug,Hash)]
+pub enum Action {
+    ADD = 0,
+    UPDATE = 1,
+    DELETE = 2,
+}
+
+impl ::protobuf::ProtobufEnum for Action {
+    fn value(&self) -> i32 {
+        *self as i32
+    }
+
+    fn from_i32(value: i32) -> ::std::option::Option<Action> {
+        match value {
+            0 => ::std::option::Option::Some(Action::ADD),
+            1 => ::std::option::Option::Some(Action::UPDATE),
+            2 => ::std::option::Option::Some(Action::DELETE),
+            _ => ::std::option::Option::None,
+        }
+    }
+
+    fn values() -> &'static [Self] {
+        static values: &'static [Action] = &[Action::ADD, Action::UPDATE, Action::DELETE];
+        values
+    }
+
+    fn enum_descriptor_static(_: Option<Action>) -> &'static ::protobuf::reflect::EnumDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::EnumDescriptor> =
+            ::protobuf::lazy::Lazy {
+                lock: ::protobuf::lazy::ONCE_INIT,
+                ptr: 0 as *const ::protobuf::reflect::EnumDescriptor,
+            };
+        unsafe {
+            descriptor.get(|| {
+                ::protobuf::reflect::EnumDescriptor::new("Action", file_descriptor_proto())
+            })
+        }
+    }
+}
+
+impl ::std::marker::Copy for Action {}
+
+impl ::std::default::Default for Action {
+    fn default() -> Self {
+        Action::ADD
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for Action {
+    fn as_ref(&self) -> ::protobuf::reflect::ProtobufValueRef {
+        ::protobuf::reflect::ProtobufValueRef::Enum(self.descriptor())
+    }
+}
+
+static file_descriptor_proto_data: &'static [u8] =
+    &[0x0a, 0x13, 0x6f, 0x62, 0x6a, 0x65, 0x63, 0x74, 0x2f, 0x70, 0x6f, 0x6c, 0x69, 0x63, 0x79,
+      0x2e, 0x70, 0x72, 0x6f, 0x74, 0x6f, 0x12, 0x0c, 0x69, 0x74, 0x68, 0x6f, 0x73, 0x2e, 0x6f,
+      0x62, 0x6a, 0x65, 0x63, 0x74, 0x22, 0x94, 0x01, 0x0a, 0x06, 0x50, 0x6f, 0x6c, 0x69, 0x63,
+      0x79, 0x12, 0x20, 0x0a, 0x0b, 0x64, 0x65, 0x73, 0x63, 0x72, 0x69, 0x70, 0x74, 0x69, 0x6f,
+      0x6e, 0x18, 0x01, 0x20, 0x01, 0x28, 0x09, 0x52, 0x0b, 0x64, 0x65, 0x73, 0x63, 0x72, 0x69,
+      0x70, 0x74, 0x69, 0x6f, 0x6e, 0x12, 0x1a, 0x0a, 0x08, 0x73, 0x75, 0x62, 0x6a, 0x65, 0x63,
+      0x74, 0x73, 0x18, 0x02, 0x20, 0x03, 0x28, 0x09, 0x52, 0x08, 0x73, 0x75, 0x62, 0x6a, 0x65,
+      0x63, 0x74, 0x73, 0x12, 0x2e, 0x0a, 0x07, 0x61, 0x63, 0x74, 0x69, 0x6f, 0x6e, 0x73, 0x18,
+      0x03, 0x20, 0x03, 0x28, 0x0e, 0x32, 0x14, 0x2e, 0x69, 0x74, 0x68, 0x6f, 0x73, 0x2e, 0x6f,
+      0x62, 0x6a, 0x65, 0x63, 0x74, 0x2e, 0x41, 0x63, 0x74, 0x69, 0x6f, 0x6e, 0x52, 0x07, 0x61,
+      0x63, 0x74, 0x69, 0x6f, 0x6e, 0x73, 0x12, 0x1c, 0x0a, 0x09, 0x74, 0x68, 0x72, 0x65, 0x73,
+      0x68, 0x6f, 0x6c, 0x64, 0x18, 0x04, 0x20, 0x01, 0x28, 0x0d, 0x52, 0x09, 0x74, 0x68, 0x72,
+      0x65, 0x73, 0x68, 0x6f, 0x6c, 0x64, 0x2a, 0x29, 0x0a, 0x06, 0x41, 0x63, 0x74, 0x69, 0x6f,
+      0x6e, 0x12, 0x07, 0x0a, 0x03, 0x41, 0x44, 0x44, 0x10, 0x00, 0x12, 0x0a, 0x0a, 0x06, 0x55,
+      0x50, 0x44, 0x41, 0x54, 0x45, 0x10, 0x01, 0x12, 0x0a, 0x0a, 0x06, 0x44, 0x45, 0x4c, 0x45,
+      0x54, 0x45, 0x10, 0x02, 0x62, 0x06, 0x70, 0x72, 0x6f, 0x74, 0x6f, 0x33];
+
+static mut file_descriptor_proto_lazy: ::protobuf::lazy::Lazy<::protobuf::descriptor::FileDescriptorProto> = ::protobuf::lazy::Lazy {
+    lock: ::protobuf::lazy::ONCE_INIT,
+    ptr: 0 as *const ::protobuf::descriptor::FileDescriptorProto,
+};
+
+fn parse_descriptor_proto() -> ::protobuf::descriptor::FileDescriptorProto {
+    ::protobuf::parse_from_bytes(file_descriptor_proto_data).unwrap()
+}
+
+pub fn file_descriptor_proto() -> &'static ::protobuf::descriptor::FileDescriptorProto {
+    unsafe { file_descriptor_proto_lazy.get(|| parse_descriptor_proto()) }
+}
+
+// TODO: Hand edited! Figure out a better solution for objecthash support
+impl ObjectHash for Policy {
+    #[inline]
+    fn objecthash<H: ObjectHasher>(&self, hasher: &mut H) {
+        let actions: Vec<u32> = self.actions.iter().map(|action| *action as u32).collect();
+
+        objecthash_struct!(
+            hasher,
+            "description" => &self.description,
+            "subjects" => &Vec::from(self.get_subjects()),
+            "actions" => &actions,
+            "threshold" => &self.threshold
+        )
+    }
+}
@@ -27,6 +27,7 @@ use protobuf::ProtobufEnum as ProtobufEnum_imported_for_functions;
 pub struct System {
     // message fields
     pub username: ::std::string::String,
+    pub description: ::std::string::String,
     // special fields
     unknown_fields: ::protobuf::UnknownFields,
     cached_size: ::protobuf::CachedSize,
@@ -81,6 +82,40 @@ impl System {
     fn mut_username_for_reflect(&mut self) -> &mut ::std::string::String {
         &mut self.username
     }
+
+    // string description = 2;
+
+    pub fn clear_description(&mut self) {
+        self.description.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_description(&mut self, v: ::std::string::String) {
+        self.description = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_description(&mut self) -> &mut ::std::string::String {
+        &mut self.description
+    }
+
+    // Take field
+    pub fn take_description(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.description, ::std::string::String::new())
+    }
+
+    pub fn get_description(&self) -> &str {
+        &self.description
+    }
+
+    fn get_description_for_reflect(&self) -> &::std::string::String {
+        &self.description
+    }
+
+    fn mut_description_for_reflect(&mut self) -> &mut ::std::string::String {
+        &mut self.description
+    }
 }
 
 impl ::protobuf::Message for System {
@@ -99,6 +134,11 @@ impl ::protobuf::Message for System {
                                                                      is,
                                                                      &mut self.username)?;
                 }
+                2 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type,
+                                                                     is,
+                                                                     &mut self.description)?;
+                }
                 _ => {
                     ::protobuf::rt::read_unknown_or_skip_group(field_number,
                                                                wire_type,
@@ -117,6 +157,9 @@ impl ::protobuf::Message for System {
         if self.username != ::std::string::String::new() {
             my_size += ::protobuf::rt::string_size(1, &self.username);
         };
+        if self.description != ::std::string::String::new() {
+            my_size += ::protobuf::rt::string_size(2, &self.description);
+        };
         my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
         self.cached_size.set(my_size);
         my_size
@@ -128,6 +171,9 @@ impl ::protobuf::Message for System {
         if self.username != ::std::string::String::new() {
             os.write_string(1, &self.username)?;
         };
+        if self.description != ::std::string::String::new() {
+            os.write_string(2, &self.description)?;
+        };
         os.write_unknown_fields(self.get_unknown_fields())?;
         ::std::result::Result::Ok(())
     }
@@ -173,6 +219,11 @@ impl ::protobuf::MessageStatic for System {
                     System::get_username_for_reflect,
                     System::mut_username_for_reflect,
                 ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "description",
+                    System::get_description_for_reflect,
+                    System::mut_description_for_reflect,
+                ));
                 ::protobuf::reflect::MessageDescriptor::new::<System>(
                     "System",
                     fields,
@@ -186,6 +237,7 @@ impl ::protobuf::MessageStatic for System {
 impl ::protobuf::Clear for System {
     fn clear(&mut self) {
         self.clear_username();
+        self.clear_description();
         self.unknown_fields.clear();
     }
 }
@@ -205,21 +257,23 @@ impl ::protobuf::reflect::ProtobufValue for System {
 static file_descriptor_proto_data: &'static [u8] =
     &[0x0a, 0x13, 0x6f, 0x62, 0x6a, 0x65, 0x63, 0x74, 0x2f, 0x73, 0x79, 0x73, 0x74, 0x65, 0x6d,
       0x2e, 0x70, 0x72, 0x6f, 0x74, 0x6f, 0x12, 0x0c, 0x69, 0x74, 0x68, 0x6f, 0x73, 0x2e, 0x6f,
-      0x62, 0x6a, 0x65, 0x63, 0x74, 0x22, 0x24, 0x0a, 0x06, 0x53, 0x79, 0x73, 0x74, 0x65, 0x6d,
+      0x62, 0x6a, 0x65, 0x63, 0x74, 0x22, 0x46, 0x0a, 0x06, 0x53, 0x79, 0x73, 0x74, 0x65, 0x6d,
       0x12, 0x1a, 0x0a, 0x08, 0x75, 0x73, 0x65, 0x72, 0x6e, 0x61, 0x6d, 0x65, 0x18, 0x01, 0x20,
-      0x01, 0x28, 0x09, 0x52, 0x08, 0x75, 0x73, 0x65, 0x72, 0x6e, 0x61, 0x6d, 0x65, 0x4a, 0xa2,
-      0x01, 0x0a, 0x06, 0x12, 0x04, 0x00, 0x00, 0x07, 0x01, 0x0a, 0x08, 0x0a, 0x01, 0x0c, 0x12,
-      0x03, 0x00, 0x00, 0x12, 0x0a, 0x08, 0x0a, 0x01, 0x02, 0x12, 0x03, 0x02, 0x08, 0x14, 0x0a,
-      0x32, 0x0a, 0x02, 0x04, 0x00, 0x12, 0x04, 0x05, 0x00, 0x07, 0x01, 0x1a, 0x26, 0x20, 0x53,
-      0x79, 0x73, 0x74, 0x65, 0x6d, 0x20, 0x55, 0x73, 0x65, 0x72, 0x20, 0x28, 0x69, 0x2e, 0x65,
-      0x2e, 0x20, 0x6e, 0x6f, 0x6e, 0x2d, 0x68, 0x75, 0x6d, 0x61, 0x6e, 0x20, 0x61, 0x63, 0x63,
-      0x6f, 0x75, 0x6e, 0x74, 0x29, 0x0a, 0x0a, 0x0a, 0x0a, 0x03, 0x04, 0x00, 0x01, 0x12, 0x03,
-      0x05, 0x08, 0x0e, 0x0a, 0x0b, 0x0a, 0x04, 0x04, 0x00, 0x02, 0x00, 0x12, 0x03, 0x06, 0x04,
-      0x18, 0x0a, 0x0d, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x00, 0x04, 0x12, 0x04, 0x06, 0x04, 0x05,
-      0x10, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x00, 0x05, 0x12, 0x03, 0x06, 0x04, 0x0a,
-      0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x00, 0x01, 0x12, 0x03, 0x06, 0x0b, 0x13, 0x0a,
-      0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x00, 0x03, 0x12, 0x03, 0x06, 0x16, 0x17, 0x62, 0x06,
-      0x70, 0x72, 0x6f, 0x74, 0x6f, 0x33];
+      0x01, 0x28, 0x09, 0x52, 0x08, 0x75, 0x73, 0x65, 0x72, 0x6e, 0x61, 0x6d, 0x65, 0x12, 0x20,
+      0x0a, 0x0b, 0x64, 0x65, 0x73, 0x63, 0x72, 0x69, 0x70, 0x74, 0x69, 0x6f, 0x6e, 0x18, 0x02,
+      0x20, 0x01, 0x28, 0x09, 0x52, 0x0b, 0x64, 0x65, 0x73, 0x63, 0x72, 0x69, 0x70, 0x74, 0x69,
+      0x6f, 0x6e, 0x4a, 0xa2, 0x01, 0x0a, 0x06, 0x12, 0x04, 0x00, 0x00, 0x07, 0x01, 0x0a, 0x08,
+      0x0a, 0x01, 0x0c, 0x12, 0x03, 0x00, 0x00, 0x12, 0x0a, 0x08, 0x0a, 0x01, 0x02, 0x12, 0x03,
+      0x02, 0x08, 0x14, 0x0a, 0x32, 0x0a, 0x02, 0x04, 0x00, 0x12, 0x04, 0x05, 0x00, 0x07, 0x01,
+      0x1a, 0x26, 0x20, 0x53, 0x79, 0x73, 0x74, 0x65, 0x6d, 0x20, 0x55, 0x73, 0x65, 0x72, 0x20,
+      0x28, 0x69, 0x2e, 0x65, 0x2e, 0x20, 0x6e, 0x6f, 0x6e, 0x2d, 0x68, 0x75, 0x6d, 0x61, 0x6e,
+      0x20, 0x61, 0x63, 0x63, 0x6f, 0x75, 0x6e, 0x74, 0x29, 0x0a, 0x0a, 0x0a, 0x0a, 0x03, 0x04,
+      0x00, 0x01, 0x12, 0x03, 0x05, 0x08, 0x0e, 0x0a, 0x0b, 0x0a, 0x04, 0x04, 0x00, 0x02, 0x00,
+      0x12, 0x03, 0x06, 0x04, 0x18, 0x0a, 0x0d, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x00, 0x04, 0x12,
+      0x04, 0x06, 0x04, 0x05, 0x10, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x00, 0x05, 0x12,
+      0x03, 0x06, 0x04, 0x0a, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x00, 0x01, 0x12, 0x03,
+      0x06, 0x0b, 0x13, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x00, 0x03, 0x12, 0x03, 0x06,
+      0x16, 0x17, 0x62, 0x06, 0x70, 0x72, 0x6f, 0x74, 0x6f, 0x33];
 
 static mut file_descriptor_proto_lazy: ::protobuf::lazy::Lazy<::protobuf::descriptor::FileDescriptorProto> = ::protobuf::lazy::Lazy {
     lock: ::protobuf::lazy::ONCE_INIT,
@@ -237,6 +291,10 @@ pub fn file_descriptor_proto() -> &'static ::protobuf::descriptor::FileDescripto
 impl ObjectHash for System {
     #[inline]
     fn objecthash<H: ObjectHasher>(&self, hasher: &mut H) {
-        objecthash_struct!(hasher, "username" => &self.username);
+        objecthash_struct!(
+            hasher,
+            "username" => &self.username,
+            "description" => &self.description
+        );
     }
 }
@@ -28,6 +28,10 @@ pub struct Op {
     pub optype: Type,
     pub path: ::std::string::String,
     object: ::protobuf::SingularPtrField<super::object::Object>,
+    pub recursive: bool,
+    pub expected_version: u64,
+    pub expected_updated_id: ::std::vec::Vec<u8>,
+    pub destination: ::std::string::String,
     // special fields
     unknown_fields: ::protobuf::UnknownFields,
     cached_size: ::protobuf::CachedSize,
@@ -147,6 +151,120 @@ impl Op {
                               -> &mut ::protobuf::SingularPtrField<super::object::Object> {
         &mut self.object
     }
+
+    // bool recursive = 4;
+
+    pub fn clear_recursive(&mut self) {
+        self.recursive = false;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_recursive(&mut self, v: bool) {
+        self.recursive = v;
+    }
+
+    pub fn get_recursive(&self) -> bool {
+        self.recursive
+    }
+
+    fn get_recursive_for_reflect(&self) -> &bool {
+        &self.recursive
+    }
+
+    fn mut_recursive_for_reflect(&mut self) -> &mut bool {
+        &mut self.recursive
+    }
+
+    // uint64 expected_version = 5;
+
+    pub fn clear_expected_version(&mut self) {
+        self.expected_version = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_expected_version(&mut self, v: u64) {
+        self.expected_version = v;
+    }
+
+    pub fn get_expected_version(&self) -> u64 {
+        self.expected_version
+    }
+
+    fn get_expected_version_for_reflect(&self) -> &u64 {
+        &self.expected_version
+    }
+
+    fn mut_expected_version_for_reflect(&mut self) -> &mut u64 {
+        &mut self.expected_version
+    }
+
+    // bytes expected_updated_id = 6;
+
+    pub fn clear_expected_updated_id(&mut self) {
+        self.expected_updated_id.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_expected_updated_id(&mut self, v: ::std::vec::Vec<u8>) {
+        self.expected_updated_id = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_expected_updated_id(&mut self) -> &mut ::std::vec::Vec<u8> {
+        &mut self.expected_updated_id
+    }
+
+    // Take field
+    pub fn take_expected_updated_id(&mut self) -> ::std::vec::Vec<u8> {
+        ::std::mem::replace(&mut self.expected_updated_id, ::std::vec::Vec::new())
+    }
+
+    pub fn get_expected_updated_id(&self) -> &[u8] {
+        &self.expected_updated_id
+    }
+
+    fn get_expected_updated_id_for_reflect(&self) -> &::std::vec::Vec<u8> {
+        &self.expected_updated_id
+    }
+
+    fn mut_expected_updated_id_for_reflect(&mut self) -> &mut ::std::vec::Vec<u8> {
+        &mut self.expected_updated_id
+    }
+
+    // string destination = 7;
+
+    pub fn clear_destination(&mut self) {
+        self.destination.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_destination(&mut self, v: ::std::string::String) {
+        self.destination = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_destination(&mut self) -> &mut ::std::string::String {
+        &mut self.destination
+    }
+
+    // Take field
+    pub fn take_destination(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.destination, ::std::string::String::new())
+    }
+
+    pub fn get_destination(&self) -> &str {
+        &self.destination
+    }
+
+    fn get_destination_for_reflect(&self) -> &::std::string::String {
+        &self.destination
+    }
+
+    fn mut_destination_for_reflect(&mut self) -> &mut ::std::string::String {
+        &mut self.destination
+    }
 }
 
 impl ::protobuf::Message for Op {
@@ -175,6 +293,30 @@ impl ::protobuf::Message for Op {
                 3 => {
                     ::protobuf::rt::read_singular_message_into(wire_type, is, &mut self.object)?;
                 }
+                4 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    };
+                    let tmp = is.read_bool()?;
+                    self.recursive = tmp;
+                }
+                5 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    };
+                    let tmp = is.read_uint64()?;
+                    self.expected_version = tmp;
+                }
+                6 => {
+                    ::protobuf::rt::read_singular_proto3_bytes_into(wire_type,
+                                                                    is,
+                                                                    &mut self.expected_updated_id)?;
+                }
+                7 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type,
+                                                                     is,
+                                                                     &mut self.destination)?;
+                }
                 _ => {
                     ::protobuf::rt::read_unknown_or_skip_group(field_number,
                                                                wire_type,
@@ -200,6 +342,20 @@ impl ::protobuf::Message for Op {
             let len = v.compute_size();
             my_size += 1 + ::protobuf::rt::compute_raw_varint32_size(len) + len;
         };
+        if self.recursive != false {
+            my_size += 2;
+        };
+        if self.expected_version != 0 {
+            my_size += ::protobuf::rt::value_size(5,
+                                                  self.expected_version,
+                                                  ::protobuf::wire_format::WireTypeVarint);
+        };
+        if self.expected_updated_id != ::std::vec::Vec::new() {
+            my_size += ::protobuf::rt::bytes_size(6, &self.expected_updated_id);
+        };
+        if self.destination != ::std::string::String::new() {
+            my_size += ::protobuf::rt::string_size(7, &self.destination);
+        };
         my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
         self.cached_size.set(my_size);
         my_size
@@ -219,6 +375,18 @@ impl ::protobuf::Message for Op {
             os.write_raw_varint32(v.get_cached_size())?;
             v.write_to_with_cached_sizes(os)?;
         };
+        if self.recursive != false {
+            os.write_bool(4, self.recursive)?;
+        };
+        if self.expected_version != 0 {
+            os.write_uint64(5, self.expected_version)?;
+        };
+        if self.expected_updated_id != ::std::vec::Vec::new() {
+            os.write_bytes(6, &self.expected_updated_id)?;
+        };
+        if self.destination != ::std::string::String::new() {
+            os.write_string(7, &self.destination)?;
+        };
         os.write_unknown_fields(self.get_unknown_fields())?;
         ::std::result::Result::Ok(())
     }
@@ -274,6 +442,26 @@ impl ::protobuf::MessageStatic for Op {
                     Op::get_object_for_reflect,
                     Op::mut_object_for_reflect,
                 ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBool>(
+                    "recursive",
+                    Op::get_recursive_for_reflect,
+                    Op::mut_recursive_for_reflect,
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                    "expected_version",
+                    Op::get_expected_version_for_reflect,
+                    Op::mut_expected_version_for_reflect,
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBytes>(
+                    "expected_updated_id",
+                    Op::get_expected_updated_id_for_reflect,
+                    Op::mut_expected_updated_id_for_reflect,
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "destination",
+                    Op::get_destination_for_reflect,
+                    Op::mut_destination_for_reflect,
+                ));
                 ::protobuf::reflect::MessageDescriptor::new::<Op>(
                     "Op",
                     fields,
@@ -289,6 +477,10 @@ impl ::protobuf::Clear for Op {
         self.clear_optype();
         self.clear_path();
         self.clear_object();
+        self.clear_recursive();
+        self.clear_expected_version();
+        self.clear_expected_updated_id();
+        self.clear_destination();
         self.unknown_fields.clear();
     }
 }
@@ -308,6 +500,9 @@ impl ::protobuf::reflect::ProtobufValue for Op {
 #[derive(Clone,PartialEq,Eq,Debug,Hash)]
 pub enum Type {
     ADD = 0,
+    DELETE = 1,
+    UPDATE = 2,
+    MOVE = 3,
 }
 
 impl ::protobuf::ProtobufEnum for Type {
@@ -318,12 +513,15 @@ impl ::protobuf::ProtobufEnum for Type {
     fn from_i32(value: i32) -> ::std::option::Option<Type> {
         match value {
             0 => ::std::option::Option::Some(Type::ADD),
+            1 => ::std::option::Option::Some(Type::DELETE),
+            2 => ::std::option::Option::Some(Type::UPDATE),
+            3 => ::std::option::Option::Some(Type::MOVE),
             _ => ::std::option::Option::None,
         }
     }
 
     fn values() -> &'static [Self] {
-        static values: &'static [Type] = &[Type::ADD];
+        static values: &'static [Type] = &[Type::ADD, Type::DELETE, Type::UPDATE, Type::MOVE];
         values
     }
 
@@ -358,41 +556,54 @@ impl ::protobuf::reflect::ProtobufValue for Type {
 static file_descriptor_proto_data: &'static [u8] =
     &[0x0a, 0x08, 0x6f, 0x70, 0x2e, 0x70, 0x72, 0x6f, 0x74, 0x6f, 0x12, 0x05, 0x69, 0x74, 0x68,
       0x6f, 0x73, 0x1a, 0x0c, 0x6f, 0x62, 0x6a, 0x65, 0x63, 0x74, 0x2e, 0x70, 0x72, 0x6f, 0x74,
-      0x6f, 0x22, 0x64, 0x0a, 0x02, 0x4f, 0x70, 0x12, 0x23, 0x0a, 0x06, 0x6f, 0x70, 0x74, 0x79,
-      0x70, 0x65, 0x18, 0x01, 0x20, 0x01, 0x28, 0x0e, 0x32, 0x0b, 0x2e, 0x69, 0x74, 0x68, 0x6f,
-      0x73, 0x2e, 0x54, 0x79, 0x70, 0x65, 0x52, 0x06, 0x6f, 0x70, 0x74, 0x79, 0x70, 0x65, 0x12,
-      0x12, 0x0a, 0x04, 0x70, 0x61, 0x74, 0x68, 0x18, 0x02, 0x20, 0x01, 0x28, 0x09, 0x52, 0x04,
-      0x70, 0x61, 0x74, 0x68, 0x12, 0x25, 0x0a, 0x06, 0x6f, 0x62, 0x6a, 0x65, 0x63, 0x74, 0x18,
-      0x03, 0x20, 0x01, 0x28, 0x0b, 0x32, 0x0d, 0x2e, 0x69, 0x74, 0x68, 0x6f, 0x73, 0x2e, 0x4f,
-      0x62, 0x6a, 0x65, 0x63, 0x74, 0x52, 0x06, 0x6f, 0x62, 0x6a, 0x65, 0x63, 0x74, 0x2a, 0x0f,
-      0x0a, 0x04, 0x54, 0x79, 0x70, 0x65, 0x12, 0x07, 0x0a, 0x03, 0x41, 0x44, 0x44, 0x10, 0x00,
-      0x4a, 0x89, 0x03, 0x0a, 0x06, 0x12, 0x04, 0x00, 0x00, 0x0f, 0x01, 0x0a, 0x08, 0x0a, 0x01,
-      0x0c, 0x12, 0x03, 0x00, 0x00, 0x12, 0x0a, 0x08, 0x0a, 0x01, 0x02, 0x12, 0x03, 0x02, 0x08,
-      0x0d, 0x0a, 0x09, 0x0a, 0x02, 0x03, 0x00, 0x12, 0x03, 0x04, 0x07, 0x15, 0x0a, 0x0a, 0x0a,
-      0x02, 0x05, 0x00, 0x12, 0x04, 0x06, 0x00, 0x08, 0x01, 0x0a, 0x0a, 0x0a, 0x03, 0x05, 0x00,
-      0x01, 0x12, 0x03, 0x06, 0x05, 0x09, 0x0a, 0x0b, 0x0a, 0x04, 0x05, 0x00, 0x02, 0x00, 0x12,
-      0x03, 0x07, 0x02, 0x0a, 0x0a, 0x0c, 0x0a, 0x05, 0x05, 0x00, 0x02, 0x00, 0x01, 0x12, 0x03,
-      0x07, 0x02, 0x05, 0x0a, 0x0c, 0x0a, 0x05, 0x05, 0x00, 0x02, 0x00, 0x02, 0x12, 0x03, 0x07,
-      0x08, 0x09, 0x0a, 0x41, 0x0a, 0x02, 0x04, 0x00, 0x12, 0x04, 0x0b, 0x00, 0x0f, 0x01, 0x1a,
-      0x35, 0x20, 0x4f, 0x70, 0x73, 0x20, 0x6d, 0x61, 0x6b, 0x65, 0x20, 0x6d, 0x6f, 0x64, 0x69,
-      0x66, 0x69, 0x63, 0x61, 0x74, 0x69, 0x6f, 0x6e, 0x73, 0x20, 0x74, 0x6f, 0x20, 0x74, 0x68,
-      0x65, 0x20, 0x73, 0x74, 0x61, 0x74, 0x65, 0x20, 0x6f, 0x66, 0x20, 0x74, 0x68, 0x65, 0x20,
-      0x64, 0x61, 0x74, 0x61, 0x62, 0x61, 0x73, 0x65, 0x0a, 0x0a, 0x0a, 0x0a, 0x03, 0x04, 0x00,
-      0x01, 0x12, 0x03, 0x0b, 0x08, 0x0a, 0x0a, 0x0b, 0x0a, 0x04, 0x04, 0x00, 0x02, 0x00, 0x12,
-      0x03, 0x0c, 0x02, 0x14, 0x0a, 0x0d, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x00, 0x04, 0x12, 0x04,
-      0x0c, 0x02, 0x0b, 0x0c, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x00, 0x06, 0x12, 0x03,
-      0x0c, 0x02, 0x06, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x00, 0x01, 0x12, 0x03, 0x0c,
-      0x07, 0x0d, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x00, 0x03, 0x12, 0x03, 0x0c, 0x12,
-      0x13, 0x0a, 0x0b, 0x0a, 0x04, 0x04, 0x00, 0x02, 0x01, 0x12, 0x03, 0x0d, 0x02, 0x14, 0x0a,
-      0x0d, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x01, 0x04, 0x12, 0x04, 0x0d, 0x02, 0x0c, 0x14, 0x0a,
-      0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x01, 0x05, 0x12, 0x03, 0x0d, 0x02, 0x08, 0x0a, 0x0c,
-      0x0a, 0x05, 0x04, 0x00, 0x02, 0x01, 0x01, 0x12, 0x03, 0x0d, 0x09, 0x0d, 0x0a, 0x0c, 0x0a,
-      0x05, 0x04, 0x00, 0x02, 0x01, 0x03, 0x12, 0x03, 0x0d, 0x12, 0x13, 0x0a, 0x0b, 0x0a, 0x04,
-      0x04, 0x00, 0x02, 0x02, 0x12, 0x03, 0x0e, 0x02, 0x14, 0x0a, 0x0d, 0x0a, 0x05, 0x04, 0x00,
-      0x02, 0x02, 0x04, 0x12, 0x04, 0x0e, 0x02, 0x0d, 0x14, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00,
-      0x02, 0x02, 0x06, 0x12, 0x03, 0x0e, 0x02, 0x08, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02,
-      0x02, 0x01, 0x12, 0x03, 0x0e, 0x09, 0x0f, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x02,
-      0x03, 0x12, 0x03, 0x0e, 0x12, 0x13, 0x62, 0x06, 0x70, 0x72, 0x6f, 0x74, 0x6f, 0x33];
+      0x6f, 0x22, 0xff, 0x01, 0x0a, 0x02, 0x4f, 0x70, 0x12, 0x23, 0x0a, 0x06, 0x6f, 0x70, 0x74,
+      0x79, 0x70, 0x65, 0x18, 0x01, 0x20, 0x01, 0x28, 0x0e, 0x32, 0x0b, 0x2e, 0x69, 0x74, 0x68,
+      0x6f, 0x73, 0x2e, 0x54, 0x79, 0x70, 0x65, 0x52, 0x06, 0x6f, 0x70, 0x74, 0x79, 0x70, 0x65,
+      0x12, 0x12, 0x0a, 0x04, 0x70, 0x61, 0x74, 0x68, 0x18, 0x02, 0x20, 0x01, 0x28, 0x09, 0x52,
+      0x04, 0x70, 0x61, 0x74, 0x68, 0x12, 0x25, 0x0a, 0x06, 0x6f, 0x62, 0x6a, 0x65, 0x63, 0x74,
+      0x18, 0x03, 0x20, 0x01, 0x28, 0x0b, 0x32, 0x0d, 0x2e, 0x69, 0x74, 0x68, 0x6f, 0x73, 0x2e,
+      0x4f, 0x62, 0x6a, 0x65, 0x63, 0x74, 0x52, 0x06, 0x6f, 0x62, 0x6a, 0x65, 0x63, 0x74, 0x12,
+      0x1c, 0x0a, 0x09, 0x72, 0x65, 0x63, 0x75, 0x72, 0x73, 0x69, 0x76, 0x65, 0x18, 0x04, 0x20,
+      0x01, 0x28, 0x08, 0x52, 0x09, 0x72, 0x65, 0x63, 0x75, 0x72, 0x73, 0x69, 0x76, 0x65, 0x12,
+      0x29, 0x0a, 0x10, 0x65, 0x78, 0x70, 0x65, 0x63, 0x74, 0x65, 0x64, 0x5f, 0x76, 0x65, 0x72,
+      0x73, 0x69, 0x6f, 0x6e, 0x18, 0x05, 0x20, 0x01, 0x28, 0x04, 0x52, 0x0f, 0x65, 0x78, 0x70,
+      0x65, 0x63, 0x74, 0x65, 0x64, 0x56, 0x65, 0x72, 0x73, 0x69, 0x6f, 0x6e, 0x12, 0x2e, 0x0a,
+      0x13, 0x65, 0x78, 0x70, 0x65, 0x63, 0x74, 0x65, 0x64, 0x5f, 0x75, 0x70, 0x64, 0x61, 0x74,
+      0x65, 0x64, 0x5f, 0x69, 0x64, 0x18, 0x06, 0x20, 0x01, 0x28, 0x0c, 0x52, 0x11, 0x65, 0x78,
+      0x70, 0x65, 0x63, 0x74, 0x65, 0x64, 0x55, 0x70, 0x64, 0x61, 0x74, 0x65, 0x64, 0x49, 0x64,
+      0x12, 0x20, 0x0a, 0x0b, 0x64, 0x65, 0x73, 0x74, 0x69, 0x6e, 0x61, 0x74, 0x69, 0x6f, 0x6e,
+      0x18, 0x07, 0x20, 0x01, 0x28, 0x09, 0x52, 0x0b, 0x64, 0x65, 0x73, 0x74, 0x69, 0x6e, 0x61,
+      0x74, 0x69, 0x6f, 0x6e, 0x2a, 0x31, 0x0a, 0x04, 0x54, 0x79, 0x70, 0x65, 0x12, 0x07, 0x0a,
+      0x03, 0x41, 0x44, 0x44, 0x10, 0x00, 0x12, 0x0a, 0x0a, 0x06, 0x44, 0x45, 0x4c, 0x45, 0x54,
+      0x45, 0x10, 0x01, 0x12, 0x0a, 0x0a, 0x06, 0x55, 0x50, 0x44, 0x41, 0x54, 0x45, 0x10, 0x02,
+      0x12, 0x08, 0x0a, 0x04, 0x4d, 0x4f, 0x56, 0x45, 0x10, 0x03, 0x4a, 0x89, 0x03, 0x0a, 0x06,
+      0x12, 0x04, 0x00, 0x00, 0x0f, 0x01, 0x0a, 0x08, 0x0a, 0x01, 0x0c, 0x12, 0x03, 0x00, 0x00,
+      0x12, 0x0a, 0x08, 0x0a, 0x01, 0x02, 0x12, 0x03, 0x02, 0x08, 0x0d, 0x0a, 0x09, 0x0a, 0x02,
+      0x03, 0x00, 0x12, 0x03, 0x04, 0x07, 0x15, 0x0a, 0x0a, 0x0a, 0x02, 0x05, 0x00, 0x12, 0x04,
+      0x06, 0x00, 0x08, 0x01, 0x0a, 0x0a, 0x0a, 0x03, 0x05, 0x00, 0x01, 0x12, 0x03, 0x06, 0x05,
+      0x09, 0x0a, 0x0b, 0x0a, 0x04, 0x05, 0x00, 0x02, 0x00, 0x12, 0x03, 0x07, 0x02, 0x0a, 0x0a,
+      0x0c, 0x0a, 0x05, 0x05, 0x00, 0x02, 0x00, 0x01, 0x12, 0x03, 0x07, 0x02, 0x05, 0x0a, 0x0c,
+      0x0a, 0x05, 0x05, 0x00, 0x02, 0x00, 0x02, 0x12, 0x03, 0x07, 0x08, 0x09, 0x0a, 0x41, 0x0a,
+      0x02, 0x04, 0x00, 0x12, 0x04, 0x0b, 0x00, 0x0f, 0x01, 0x1a, 0x35, 0x20, 0x4f, 0x70, 0x73,
+      0x20, 0x6d, 0x61, 0x6b, 0x65, 0x20, 0x6d, 0x6f, 0x64, 0x69, 0x66, 0x69, 0x63, 0x61, 0x74,
+      0x69, 0x6f, 0x6e, 0x73, 0x20, 0x74, 0x6f, 0x20, 0x74, 0x68, 0x65, 0x20, 0x73, 0x74, 0x61,
+      0x74, 0x65, 0x20, 0x6f, 0x66, 0x20, 0x74, 0x68, 0x65, 0x20, 0x64, 0x61, 0x74, 0x61, 0x62,
+      0x61, 0x73, 0x65, 0x0a, 0x0a, 0x0a, 0x0a, 0x03, 0x04, 0x00, 0x01, 0x12, 0x03, 0x0b, 0x08,
+      0x0a, 0x0a, 0x0b, 0x0a, 0x04, 0x04, 0x00, 0x02, 0x00, 0x12, 0x03, 0x0c, 0x02, 0x14, 0x0a,
+      0x0d, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x00, 0x04, 0x12, 0x04, 0x0c, 0x02, 0x0b, 0x0c, 0x0a,
+      0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x00, 0x06, 0x12, 0x03, 0x0c, 0x02, 0x06, 0x0a, 0x0c,
+      0x0a, 0x05, 0x04, 0x00, 0x02, 0x00, 0x01, 0x12, 0x03, 0x0c, 0x07, 0x0d, 0x0a, 0x0c, 0x0a,
+      0x05, 0x04, 0x00, 0x02, 0x00, 0x03, 0x12, 0x03, 0x0c, 0x12, 0x13, 0x0a, 0x0b, 0x0a, 0x04,
+      0x04, 0x00, 0x02, 0x01, 0x12, 0x03, 0x0d, 0x02, 0x14, 0x0a, 0x0d, 0x0a, 0x05, 0x04, 0x00,
+      0x02, 0x01, 0x04, 0x12, 0x04, 0x0d, 0x02, 0x0c, 0x14, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00,
+      0x02, 0x01, 0x05, 0x12, 0x03, 0x0d, 0x02, 0x08, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02,
+      0x01, 0x01, 0x12, 0x03, 0x0d, 0x09, 0x0d, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x01,
+      0x03, 0x12, 0x03, 0x0d, 0x12, 0x13, 0x0a, 0x0b, 0x0a, 0x04, 0x04, 0x00, 0x02, 0x02, 0x12,
+      0x03, 0x0e, 0x02, 0x14, 0x0a, 0x0d, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x02, 0x04, 0x12, 0x04,
+      0x0e, 0x02, 0x0d, 0x14, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x02, 0x06, 0x12, 0x03,
+      0x0e, 0x02, 0x08, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x02, 0x01, 0x12, 0x03, 0x0e,
+      0x09, 0x0f, 0x0a, 0x0c, 0x0a, 0x05, 0x04, 0x00, 0x02, 0x02, 0x03, 0x12, 0x03, 0x0e, 0x12,
+      0x13, 0x62, 0x06, 0x70, 0x72, 0x6f, 0x74, 0x6f, 0x33];
 
 static mut file_descriptor_proto_lazy: ::protobuf::lazy::Lazy<::protobuf::descriptor::FileDescriptorProto> = ::protobuf::lazy::Lazy {
     lock: ::protobuf::lazy::ONCE_INIT,
@@ -415,7 +626,11 @@ impl ObjectHash for Op {
             hasher,
             "optype" => &(self.optype as u32),
             "path" => &self.path,
-            "object" => self.get_object()
+            "object" => self.get_object(),
+            "recursive" => &(self.recursive as u32),
+            "expected_version" => &(self.expected_version as i64),
+            "expected_updated_id" => &self.expected_updated_id,
+            "destination" => &self.destination
         )
     }
 }
@@ -120,6 +120,17 @@ impl Path {
     pub fn entry_name(&self) -> Option<&str> {
         self.0.rsplitn(2, SEPARATOR).next()
     }
+
+    /// Is this path the given base path, or located underneath it?
+    pub fn starts_with<P: AsRef<Path>>(&self, base: P) -> bool {
+        let base = base.as_ref();
+
+        if base == Path::root() || self == base {
+            return true;
+        }
+
+        self.0.starts_with(&base.0) && self.0[base.0.len()..].starts_with(SEPARATOR)
+    }
 }
 
 impl AsRef<str> for Path {
@@ -213,6 +224,15 @@ mod tests {
         assert_eq!(example_path().entry_name().unwrap(), "baz");
     }
 
+    #[test]
+    fn path_starts_with() {
+        assert!(example_path().starts_with(Path::root()));
+        assert!(example_path().starts_with(Path::new("/foo/bar").unwrap()));
+        assert!(example_path().starts_with(example_path()));
+        assert!(!example_path().starts_with(Path::new("/foo/ba").unwrap()));
+        assert!(!Path::new("/foo").unwrap().starts_with(example_path()));
+    }
+
     #[test]
     fn root_components() {
         assert!(Path::root().components().is_empty());
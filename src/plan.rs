@@ -0,0 +1,190 @@
+//! plan.rs: Changes needed to bring a subtree of the directory into a desired state
+//!
+//! A plan is computed by diffing a desired state file (see `manifest::State`) against the
+//! directory. Along with a human-readable list of changes, it contains an unsigned block holding
+//! the ops which make them, which can be reviewed and signed like any other proposed block.
+//!
+
+use block::Block;
+use entry::{Class, Entry};
+use manifest::EntrySpec;
+use object::Object;
+use path::PathBuf;
+
+/// Changes needed to bring the directory into a desired state
+#[derive(Debug)]
+pub struct Plan {
+    /// Changes to be made, in the order their ops appear in the block
+    pub changes: Vec<Change>,
+
+    /// Unsigned block containing the ops which make the changes
+    pub block: Block,
+}
+
+/// A change to a single entry
+#[derive(Debug, PartialEq)]
+pub enum Change {
+    /// Add a new entry
+    Add {
+        /// Path to the entry
+        path: PathBuf,
+
+        /// Class of the new entry
+        class: Class,
+
+        /// Fields of the new entry
+        fields: Vec<(&'static str, String)>,
+    },
+
+    /// Replace an existing entry, changing some of its fields
+    Update {
+        /// Path to the entry
+        path: PathBuf,
+
+        /// Fields which change
+        fields: Vec<FieldChange>,
+    },
+
+    /// Delete an existing entry
+    Delete {
+        /// Path to the entry
+        path: PathBuf,
+
+        /// Whether the entry's children are deleted along with it
+        recursive: bool,
+    },
+}
+
+/// A field whose value changes
+#[derive(Debug, PartialEq)]
+pub struct FieldChange {
+    /// Name of the field
+    pub name: &'static str,
+
+    /// Current value of the field
+    pub old: String,
+
+    /// Desired value of the field
+    pub new: String,
+}
+
+/// Fill in the fields of a desired credential which can't be given in a desired state file
+/// (the sealed private key, and the validity window unless a lifetime was given) from the
+/// existing one, so that only the fields which were actually specified show up as changes
+pub fn carry_over(current: &Entry, desired: &mut Object, spec: &EntrySpec) {
+    let current = match *current {
+        Entry::Credential(ref credential) if desired.has_credential() => credential,
+        _ => return,
+    };
+
+    let desired = desired.mut_credential();
+    desired.set_keyid(current.get_keyid().to_vec());
+    desired.set_credential_alg(current.get_credential_alg().to_owned());
+    desired.set_sealing_alg(current.get_sealing_alg());
+    desired.set_encrypted_value(current.get_encrypted_value().to_vec());
+    desired.set_salt(current.get_salt().to_vec());
+
+    if spec.lifetime.is_none() {
+        desired.set_not_before(current.get_not_before());
+        desired.set_not_after(current.get_not_after());
+    }
+}
+
+/// Compare the fields of two entries of the same class, returning those which differ
+pub fn diff_fields(current: &Entry, desired: &Entry) -> Vec<FieldChange> {
+    current
+        .fields()
+        .into_iter()
+        .zip(desired.fields())
+        .filter(|&((_, ref old), (_, ref new))| old != new)
+        .map(|((name, old), (_, new))| {
+            FieldChange {
+                name: name,
+                old: old,
+                new: new,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use entry::Entry;
+    use manifest::EntrySpec;
+    use object::credential::{self, Credential};
+    use object::org_unit::OrgUnit;
+    use plan::{self, FieldChange};
+    use timestamp::Timestamp;
+
+    fn credential_spec(lifetime: Option<u64>) -> EntrySpec {
+        EntrySpec {
+            path: "/example.com/eng/alice/key".to_owned(),
+            class: "credential".to_owned(),
+            description: Some("Alice's key".to_owned()),
+            public_key: Some("AAAA".to_owned()),
+            lifetime: lifetime,
+            subjects: vec![],
+            actions: vec![],
+            threshold: 0,
+            expected_version: None,
+        }
+    }
+
+    #[test]
+    fn changed_fields() {
+        let mut current = OrgUnit::new();
+        current.set_description("Engineering".to_owned());
+
+        let mut desired = OrgUnit::new();
+        desired.set_description("Product engineering".to_owned());
+
+        assert_eq!(
+            plan::diff_fields(&Entry::OrgUnit(current.clone()), &Entry::OrgUnit(desired)),
+            vec![
+                FieldChange {
+                    name: "description",
+                    old: "Engineering".to_owned(),
+                    new: "Product engineering".to_owned(),
+                },
+            ]
+        );
+
+        let unchanged = Entry::OrgUnit(current.clone());
+        assert!(plan::diff_fields(&Entry::OrgUnit(current), &unchanged).is_empty());
+    }
+
+    #[test]
+    fn credential_carry_over() {
+        let mut current = Credential::new();
+        current.set_credential_type(credential::Type::SIGNATURE_KEY_PAIR);
+        current.set_keyid(vec![1, 2, 3]);
+        current.set_encrypted_value(vec![4, 5, 6]);
+        current.set_salt(vec![7, 8, 9]);
+        current.set_public_key(vec![0, 0, 0]);
+        current.set_not_before(1);
+        current.set_not_after(2);
+        current.set_description("Alice's key".to_owned());
+        let current = Entry::Credential(current);
+
+        // Only the fields given in the spec are compared
+        let spec = credential_spec(None);
+        let mut desired = spec.to_object(Timestamp::now()).unwrap();
+        plan::carry_over(&current, &mut desired, &spec);
+
+        let desired = Entry::from_object(&mut desired).unwrap();
+        assert!(plan::diff_fields(&current, &desired).is_empty());
+
+        // Giving a lifetime renews the credential
+        let spec = credential_spec(Some(60));
+        let mut desired = spec.to_object(Timestamp::now()).unwrap();
+        plan::carry_over(&current, &mut desired, &spec);
+
+        let desired = Entry::from_object(&mut desired).unwrap();
+        let changed: Vec<&str> = plan::diff_fields(&current, &desired)
+            .iter()
+            .map(|change| change.name)
+            .collect();
+
+        assert_eq!(changed, vec!["not_before", "not_after"]);
+    }
+}
@@ -2,47 +2,76 @@
 //!
 //! This is presently a bit of a dumping ground for server-side functionality
 //!
-//! Logic in here shouldn't get too complicated. This is (accidentally) prevented in part by
-//! the limitations of Rust's type system, as higher ranked trait bounds on Adapter prevent
-//! access to the associated types for transactions.
+//! The server is generic over its storage adapter, so ithos can be embedded with any storage
+//! engine which implements `Adapter`. Logic in here shouldn't get too complicated. This is
+//! (accidentally) prevented in part by the limitations of Rust's type system, as higher ranked
+//! trait bounds on Adapter prevent naming the associated types for transactions, so they can
+//! only be used within a single method.
 //!
 
 use adapter::Adapter;
-#[cfg(feature = "lmdb")]
-use adapter::lmdb::LmdbAdapter;
+#[cfg(feature = "memory-adapter")]
+use adapter::memory::MemoryAdapter;
 use alg::{CipherSuite, SignatureAlg, EncryptionAlg, PasswordAlg};
-use block::Body;
+use audit::{self, LogEntry, LogSigner};
+use block::{Block, Body};
 use crypto::password;
 use crypto::signing::KeyPair;
 use crypto::symmetric::{AES256GCM_KEY_SIZE, AES256GCM_NONCE_SIZE};
-use entry::Entry;
+use data_encoding::BASE64URL;
+use entry::{Class, Entry};
 use errors::*;
+use id::{BlockId, EntryId};
+use manifest::{self, DEFAULT_CREDENTIAL_LIFETIME, Manifest, State};
+use metadata::Metadata;
 use object::Object;
-use object::credential::Credential;
+use object::credential::{self, Credential};
 use object::domain::Domain;
+use object::org_unit::OrgUnit;
+use object::system::System;
 use op::{self, Op};
 use path::{Path, PathBuf};
-use protobuf::RepeatedField;
+use plan::{self, Change, Plan};
+use protobuf::{self, CodedInputStream, Message, RepeatedField};
 use ring::rand::SecureRandom;
 use setup;
+use std::io::{Read, Write};
 use std::path::Path as StdPath;
 use std::str;
 use timestamp::Timestamp;
-use transform::Transform;
-
-#[cfg(test)]
-extern crate tempdir;
+use transform::{DEFAULT_MAX_CLOCK_SKEW, Transform};
 
 /// Default comment used for the initial block
 const DEFAULT_INITIAL_BLOCK_COMMENT: &str = "Initial block";
 
-/// An ithos server
-#[cfg(feature = "lmdb")]
-pub struct Server(LmdbAdapter);
+/// An ithos server, storing the directory using the given storage adapter
+pub struct Server<A> {
+    /// Storage adapter containing the directory
+    adapter: A,
+
+    /// Number of seconds the timestamps of blocks being added may be ahead of our clock
+    max_clock_skew: u64,
+}
+
+impl<A> Server<A>
+where
+    A: for<'a> Adapter<'a>,
+{
+    /// Serve an existing directory from the given storage adapter
+    pub fn new(adapter: A) -> Server<A> {
+        Server {
+            adapter: adapter,
+            max_clock_skew: DEFAULT_MAX_CLOCK_SKEW,
+        }
+    }
+
+    /// Set the number of seconds the timestamps of blocks being added may be ahead of the
+    /// current time, e.g. to accept blocks proposed on machines whose clocks run fast
+    pub fn set_max_clock_skew(&mut self, seconds: u64) {
+        self.max_clock_skew = seconds;
+    }
 
-impl Server {
     /// Create a new ithos database at the given filesystem path
-    #[cfg(feature = "lmdb")]
     pub fn create_database(
         path: &StdPath,
         rng: &SecureRandom,
@@ -50,30 +79,31 @@ impl Server {
         admin_username: &str,
         admin_password: &str,
     ) -> Result<()> {
-        // We presently only support one ciphersuite
-        assert_eq!(ciphersuite, CipherSuite::Ed25519_AES256GCM_SHA256);
-
-        let admin_keypair_salt = password::random_salt(rng)?;
-
-        let mut admin_symmetric_key = [0u8; AES256GCM_KEY_SIZE];
-        password::derive(
-            PasswordAlg::SCRYPT,
-            &admin_keypair_salt,
+        Server::initialize(
+            A::create_database(path)?,
+            rng,
+            ciphersuite,
+            admin_username,
             admin_password,
-            &mut admin_symmetric_key,
-        );
+        )?;
 
-        // NOTE: Fixed nonce. The admin password should be randomly generated and never reused
-        let nonce = [0u8; AES256GCM_NONCE_SIZE];
+        Ok(())
+    }
 
-        // TODO: honor ciphersuite algorithms
-        let (admin_keypair, admin_keypair_sealed) = KeyPair::generate_and_seal(
-            SignatureAlg::Ed25519,
-            EncryptionAlg::AES256GCM,
-            rng,
-            &admin_symmetric_key,
-            &nonce,
-        )?;
+    /// Create a new directory in the given (empty) storage adapter, with an initial block
+    /// containing an administrator with the given username and password
+    pub fn initialize(
+        adapter: A,
+        rng: &SecureRandom,
+        ciphersuite: CipherSuite,
+        admin_username: &str,
+        admin_password: &str,
+    ) -> Result<Server<A>> {
+        // We presently only support one ciphersuite
+        assert_eq!(ciphersuite, CipherSuite::Ed25519_AES256GCM_SHA256);
+
+        let (admin_keypair, admin_keypair_sealed, admin_keypair_salt) =
+            generate_sealed_keypair(rng, admin_password)?;
 
         let initial_block = setup::create_log(
             ciphersuite,
@@ -84,20 +114,18 @@ impl Server {
             DEFAULT_INITIAL_BLOCK_COMMENT,
         );
 
-        let adapter = LmdbAdapter::create_database(path)?;
-
-        let mut transform = Transform::new(&adapter)?;
-        transform.apply(&initial_block)?;
-        transform.commit()?;
+        {
+            let mut transform = Transform::new(&adapter)?;
+            transform.apply(&initial_block)?;
+            transform.commit()?;
+        }
 
-        Ok(())
+        Ok(Server::new(adapter))
     }
 
     /// Open an existing ithos database
-    #[cfg(feature = "lmdb")]
-    pub fn open_database(path: &StdPath) -> Result<Server> {
-        let adapter = LmdbAdapter::open_database(path)?;
-        Ok(Server(adapter))
+    pub fn open_database(path: &StdPath) -> Result<Server<A>> {
+        Ok(Server::new(A::open_database(path)?))
     }
 
     /// Add a new `Domain` object to this ithos server
@@ -107,6 +135,7 @@ impl Server {
         domain_name: &str,
         description: Option<String>,
         comment: &str,
+        dry_run: bool,
     ) -> Result<()> {
         let mut domain_entry = Domain::new();
 
@@ -114,38 +143,536 @@ impl Server {
             domain_entry.set_description(desc);
         }
 
-        let timestamp = Timestamp::now();
         let mut path = PathBuf::new();
         path.push(&domain_name);
 
         let mut domain_entry_object = Object::new();
         domain_entry_object.set_domain(domain_entry);
 
-        let mut op = Op::new();
-        op.set_optype(op::Type::ADD);
-        op.set_path(path.into());
-        op.set_object(domain_entry_object);
+        self.add_object(admin_keypair, path, domain_entry_object, comment, dry_run)
+    }
+
+    /// Add a new `OrgUnit` object (e.g. a department or group of credentials) at the given path
+    pub fn add_org_unit(
+        &self,
+        admin_keypair: &KeyPair,
+        path: &Path,
+        description: Option<String>,
+        comment: &str,
+        dry_run: bool,
+    ) -> Result<()> {
+        let mut org_unit_entry = OrgUnit::new();
+
+        if let Some(desc) = description {
+            org_unit_entry.set_description(desc);
+        }
+
+        let mut org_unit_entry_object = Object::new();
+        org_unit_entry_object.set_org_unit(org_unit_entry);
+
+        self.add_object(
+            admin_keypair,
+            path.to_owned(),
+            org_unit_entry_object,
+            comment,
+            dry_run,
+        )
+    }
+
+    /// Add a new `System` user at the given path. The username is the last component of the path
+    pub fn add_system_user(
+        &self,
+        admin_keypair: &KeyPair,
+        path: &Path,
+        description: Option<String>,
+        comment: &str,
+        dry_run: bool,
+    ) -> Result<()> {
+        let username = match path.entry_name() {
+            Some(name) if !name.is_empty() => name,
+            _ => return Err(ErrorKind::PathInvalid(path.to_string()).into()),
+        };
+
+        let mut system_entry = System::new();
+        system_entry.set_username(username.to_owned());
+
+        if let Some(desc) = description {
+            system_entry.set_description(desc);
+        }
+
+        let mut system_entry_object = Object::new();
+        system_entry_object.set_system(system_entry);
+
+        self.add_object(
+            admin_keypair,
+            path.to_owned(),
+            system_entry_object,
+            comment,
+            dry_run,
+        )
+    }
+
+    /// Generate a new signature key pair, sealed under a key derived from the given password, and
+    /// add it to the directory as a `Credential` at the given path
+    pub fn add_credential(
+        &self,
+        admin_keypair: &KeyPair,
+        rng: &SecureRandom,
+        path: &Path,
+        password: &str,
+        description: Option<String>,
+        comment: &str,
+        dry_run: bool,
+    ) -> Result<()> {
+        let (keypair, keypair_sealed, salt) = generate_sealed_keypair(rng, password)?;
+        let timestamp = Timestamp::now();
+
+        // TODO: keyid, credential_alg
+        let mut credential_entry = Credential::new();
+        credential_entry.set_credential_type(credential::Type::SIGNATURE_KEY_PAIR);
+        credential_entry.set_sealing_alg(EncryptionAlg::AES256GCM);
+        credential_entry.set_encrypted_value(keypair_sealed);
+        credential_entry.set_salt(salt);
+        credential_entry.set_public_key(Vec::from(keypair.public_key_bytes()));
+        credential_entry.set_not_before(timestamp.to_int());
+        credential_entry.set_not_after(timestamp.extend(DEFAULT_CREDENTIAL_LIFETIME).to_int());
+
+        if let Some(desc) = description {
+            credential_entry.set_description(desc);
+        }
+
+        let mut credential_entry_object = Object::new();
+        credential_entry_object.set_credential(credential_entry);
+
+        self.add_object(
+            admin_keypair,
+            path.to_owned(),
+            credential_entry_object,
+            comment,
+            dry_run,
+        )
+    }
+
+    /// Apply all of the changes in a manifest in a single block signed by the given administrator.
+    /// In a dry run, the block is fully checked but the database is left unchanged.
+    ///
+    /// Updated credentials keep the sealed keys of the credentials they replace, along with their
+    /// validity windows unless a lifetime is given, as neither can be specified in a manifest.
+    pub fn apply_manifest(
+        &self,
+        admin_keypair: &KeyPair,
+        manifest: &Manifest,
+        dry_run: bool,
+    ) -> Result<()> {
+        let timestamp = Timestamp::now();
+        let mut ops = manifest.ops(timestamp)?;
+
+        {
+            let txn = self.adapter.ro_transaction()?;
+            let updates = ops.iter_mut().filter(
+                |op| op.get_optype() == op::Type::UPDATE,
+            );
+
+            for (op, spec) in updates.zip(&manifest.update) {
+                let path = manifest::parse_path(&spec.path)?;
+
+                // Entries which don't exist are left for the block to be rejected over
+                let direntry = match self.adapter.find_direntry(&txn, path) {
+                    Ok(direntry) => direntry,
+                    Err(Error(ErrorKind::NotFound(_), _)) => continue,
+                    Err(err) => return Err(err),
+                };
+
+                let current = self.adapter.find_entry(&txn, &direntry.id)?.deserialize()?;
+                plan::carry_over(&current, op.mut_object(), spec);
+            }
+        }
+
+        let mut block = self.propose(ops, timestamp, &manifest.comment)?;
+        admin_keypair.cosign_block(&mut block)?;
+
+        if dry_run {
+            self.dry_run_block(&block)
+        } else {
+            self.apply_block(&block)
+        }
+    }
+
+    /// Compute the changes needed to make the subtree at the root of the given desired state
+    /// match it exactly, along with an unsigned block containing the corresponding ops.
+    ///
+    /// Listed entries which don't exist are added, listed entries whose fields differ are
+    /// updated, and unlisted entries beneath the root are deleted. The block is checked against
+    /// the database in a transaction which is never committed, but not signed or authorized.
+    pub fn plan(&self, state: &State) -> Result<Plan> {
+        let root = manifest::parse_path(&state.root)?;
+        let timestamp = Timestamp::now();
+
+        let mut specs = Vec::new();
+
+        for spec in &state.entries {
+            let path = manifest::parse_path(&spec.path)?;
+
+            if !path.starts_with(root) {
+                let msg = format!("{} is outside of {}", spec.path, state.root);
+                return Err(ErrorKind::PathInvalid(msg).into());
+            }
+
+            if specs.iter().any(|&(listed, _)| listed == path) {
+                let msg = format!("{} is listed more than once", spec.path);
+                return Err(ErrorKind::ParseFailure(msg).into());
+            }
+
+            specs.push((path, spec));
+        }
+
+        // Unlisted entries are deleted, so a listed entry's parents must be listed too
+        for &(path, spec) in &specs {
+            if path == root {
+                continue;
+            }
+
+            let parent = path.parent().unwrap();
+
+            if parent != root && !specs.iter().any(|&(listed, _)| listed == parent) {
+                let msg = format!(
+                    "{} is listed but its parent {} isn't",
+                    spec.path,
+                    parent.to_string()
+                );
+                return Err(ErrorKind::PathInvalid(msg).into());
+            }
+        }
+
+        // Parents must be added before their children
+        specs.sort_by_key(|&(path, _)| path.components().len());
+
+        let mut changes = Vec::new();
+        let mut ops = Vec::new();
+
+        {
+            let txn = self.adapter.ro_transaction()?;
+
+            let existing: Vec<(PathBuf, EntryId)> = match self.adapter.walk(&txn, root) {
+                Ok(walk) => {
+                    walk.map(|result| result.map(|(path, direntry)| (path, direntry.id)))
+                        .collect::<Result<_>>()?
+                }
+                Err(Error(ErrorKind::NotFound(_), _)) => Vec::new(),
+                Err(err) => return Err(err),
+            };
+
+            // Entries are walked parents first, so only the topmost unlisted ones are deleted
+            let mut deleted: Vec<PathBuf> = Vec::new();
+
+            for (path, id) in existing {
+                if specs.iter().any(|&(listed, _)| listed == path.as_path()) ||
+                    deleted.iter().any(|parent| path.as_path().starts_with(parent))
+                {
+                    continue;
+                }
+
+                let recursive = !self.adapter.list_children(&txn, id)?.is_empty();
+
+                let mut op = Op::new();
+                op.set_optype(op::Type::DELETE);
+                op.set_path(path.as_path().to_string());
+                op.set_recursive(recursive);
+                op.set_expected_version(self.adapter.find_metadata(&txn, &id)?.get_version());
+                ops.push(op);
+
+                changes.push(Change::Delete {
+                    path: path.clone(),
+                    recursive: recursive,
+                });
+
+                deleted.push(path);
+            }
+
+            for (path, spec) in specs {
+                let mut object = spec.to_object(timestamp)?;
+
+                let direntry = match self.adapter.find_direntry(&txn, path) {
+                    Ok(direntry) => Some(direntry),
+                    Err(Error(ErrorKind::NotFound(_), _)) => None,
+                    Err(err) => return Err(err),
+                };
+
+                let mut op = Op::new();
+                op.set_path(path.to_string());
 
-        let mut transform = Transform::new(&self.0)?;
+                match direntry {
+                    Some(direntry) => {
+                        let current = self.adapter.find_entry(&txn, &direntry.id)?.deserialize()?;
+                        plan::carry_over(&current, &mut object, spec);
+
+                        let desired = Entry::from_object(&mut object.clone()).unwrap();
+
+                        if current.class() != desired.class() {
+                            let msg = format!(
+                                "{}: can't change class from {} to {}",
+                                spec.path,
+                                current.class().to_string(),
+                                desired.class().to_string()
+                            );
+                            return Err(ErrorKind::TypeInvalid(msg).into());
+                        }
+
+                        let fields = plan::diff_fields(&current, &desired);
+
+                        if fields.is_empty() {
+                            continue;
+                        }
+
+                        let metadata = self.adapter.find_metadata(&txn, &direntry.id)?;
+                        op.set_optype(op::Type::UPDATE);
+                        op.set_expected_version(metadata.get_version());
+
+                        changes.push(Change::Update {
+                            path: path.to_owned(),
+                            fields: fields,
+                        });
+                    }
+                    None => {
+                        let desired = Entry::from_object(&mut object.clone()).unwrap();
+                        op.set_optype(op::Type::ADD);
+
+                        changes.push(Change::Add {
+                            path: path.to_owned(),
+                            class: desired.class(),
+                            fields: desired.fields(),
+                        });
+                    }
+                }
+
+                op.set_object(object);
+                ops.push(op);
+            }
+        }
+
+        let block = self.propose(ops, timestamp, &state.comment)?;
+        self.transform()?.check(&block)?;
+
+        Ok(Plan {
+            changes: changes,
+            block: block,
+        })
+    }
+
+    /// Create an unsigned block containing the given ops, chained onto the current block.
+    ///
+    /// The resulting block can be serialized and passed between administrators, each of whom
+    /// adds their signature with `KeyPair::cosign_block` until enough are present to satisfy
+    /// the policies covering its ops, at which point it can be passed to `apply_block`.
+    pub fn propose(&self, ops: Vec<Op>, timestamp: Timestamp, comment: &str) -> Result<Block> {
+        let txn = self.adapter.ro_transaction()?;
 
         let mut body = Body::new();
-        body.set_parent_id(transform.block_id()?.as_ref().to_vec());
+        body.set_parent_id(self.adapter.current_block_id(&txn)?.as_ref().to_vec());
         body.set_timestamp(timestamp.to_int());
-        body.set_ops(RepeatedField::from_vec(vec![op]));
+        body.set_ops(RepeatedField::from_vec(ops));
         body.set_comment(comment.to_owned());
 
-        let block = admin_keypair.sign_block(body);
+        let mut block = Block::new();
+        block.set_body(body);
+        Ok(block)
+    }
+
+    /// Verify and apply a signed block to this ithos server
+    pub fn apply_block(&self, block: &Block) -> Result<()> {
+        let mut transform = self.transform()?;
+        transform.apply(block)?;
+        transform.commit()
+    }
 
-        // TODO: authenticate signature before committing (BIG SECURITY PROBLEM!!!)
-        transform.apply(&block)?;
-        transform.commit()?;
+    /// Verify a signed block and apply it in a transaction which is then aborted, leaving the
+    /// database unchanged. Succeeds if and only if `apply_block` would (barring other changes
+    /// being made in the meantime), so proposed blocks can be validated before being cosigned
+    pub fn dry_run_block(&self, block: &Block) -> Result<()> {
+        self.transform()?.dry_run(block)
+    }
 
-        Ok(())
+    /// Check a proposed block which may still be gathering signatures, leaving the database
+    /// unchanged. The block's structure and any versions its ops expect are checked first, then
+    /// whatever signatures it has so far are verified, and a description of each authorization
+    /// its signers still lack is returned (none means `apply_block` would accept it)
+    pub fn check_block(&self, block: &Block) -> Result<Vec<String>> {
+        self.transform()?.check(block)?;
+        self.transform()?.missing_authorizations(block)
+    }
+
+    /// Verify the integrity of this database by replaying its entire log into a scratch database.
+    ///
+    /// Every block is checked to be stored under its own ID and to chain onto its predecessor,
+    /// and replaying it re-verifies its signatures and the authorization of its ops. Afterwards
+    /// the replayed tables must match this database's byte for byte. Returns the number of
+    /// blocks which were replayed.
+    #[cfg(feature = "memory-adapter")]
+    pub fn verify(&self) -> Result<usize> {
+        let blocks = self.read_log()?;
+        let scratch = MemoryAdapter::new();
+
+        for block in &blocks {
+            let mut transform = Transform::new(&scratch)?;
+
+            transform.apply(block).chain_err(|| {
+                let msg = format!(
+                    "block {} could not be replayed",
+                    BASE64URL.encode(BlockId::of(block).as_ref())
+                );
+                ErrorKind::VerificationFailure(msg)
+            })?;
+
+            transform.commit()?;
+        }
+
+        let mismatched = self.adapter.compare_tables(&scratch)?;
+
+        if !mismatched.is_empty() {
+            let msg = format!("replayed state differs in: {}", mismatched.join(", "));
+            return Err(ErrorKind::VerificationFailure(msg).into());
+        }
+
+        Ok(blocks.len())
+    }
+
+    /// Write every block in the log, from the initial block to the current one, as a sequence
+    /// of length-delimited protobufs. Returns the number of blocks written
+    pub fn export(&self, writer: &mut Write) -> Result<usize> {
+        let blocks = self.read_log()?;
+
+        for block in &blocks {
+            block.write_length_delimited_to_writer(writer)?;
+        }
+
+        Ok(blocks.len())
+    }
+
+    /// Create a new database at the given path from a log written by `export`.
+    ///
+    /// Every block is verified and applied exactly as if it were being added to a live
+    /// database, so an import succeeds only if the log's signatures, policies and chain of
+    /// parent IDs are intact. Returns the number of blocks imported.
+    pub fn import(path: &StdPath, reader: &mut Read) -> Result<usize> {
+        Server::import_into(&A::create_database(path)?, reader)
+    }
+
+    /// Apply every block in a log written by `export` to the given (empty) storage adapter
+    fn import_into(adapter: &A, reader: &mut Read) -> Result<usize> {
+        let mut input = CodedInputStream::new(reader);
+        let mut count = 0;
+
+        while !input.eof()? {
+            let block: Block = protobuf::parse_length_delimited_from(&mut input)?;
+            let mut transform = Transform::new(adapter)?;
+
+            transform.apply(&block).chain_err(|| {
+                let msg = format!(
+                    "block {} ({} in the log) could not be imported",
+                    BASE64URL.encode(BlockId::of(&block).as_ref()),
+                    count
+                );
+                ErrorKind::VerificationFailure(msg)
+            })?;
+
+            transform.commit()?;
+            count += 1;
+        }
+
+        if count == 0 {
+            return Err(ErrorKind::ParseFailure("log is empty".to_owned()).into());
+        }
+
+        Ok(count)
+    }
+
+    /// Obtain a read-only view of the directory as it was immediately after the given block.
+    ///
+    /// The view is built by replaying the log up to and including that block into an in-memory
+    /// database, and supports all of the same read APIs as the current directory.
+    #[cfg(feature = "memory-adapter")]
+    pub fn at_block(&self, block_id: &BlockId) -> Result<Server<MemoryAdapter>> {
+        let txn = self.adapter.ro_transaction()?;
+        let mut block_id = *block_id;
+        let mut blocks = Vec::new();
+
+        loop {
+            let block = self.adapter.find_block(&txn, &block_id).chain_err(|| {
+                let msg = format!("no block with ID {}", BASE64URL.encode(block_id.as_ref()));
+                ErrorKind::NotFound(msg)
+            })?;
+
+            let parent_id = BlockId::from_bytes(block.get_body().get_parent_id())?;
+            blocks.push(block);
+
+            if parent_id == BlockId::zero() {
+                break;
+            }
+
+            block_id = parent_id;
+        }
+
+        blocks.reverse();
+        Self::replay(blocks.into_iter().map(Ok))
+    }
+
+    /// Obtain a read-only view of the directory as it was at the given time, i.e. immediately
+    /// after the last block whose timestamp is not later than it
+    #[cfg(feature = "memory-adapter")]
+    pub fn at_time(&self, timestamp: Timestamp) -> Result<Server<MemoryAdapter>> {
+        let txn = self.adapter.ro_transaction()?;
+        let current_height = self.adapter.current_block_height(&txn)?;
+
+        // Block timestamps never decrease, so the first match walking backwards is the latest
+        for height in (0..current_height + 1).rev() {
+            let block = self.adapter.find_block_by_height(&txn, height)?;
+
+            if block.get_body().get_timestamp() <= timestamp.to_int() {
+                return Self::replay(self.adapter.find_blocks(&txn, 0..height + 1));
+            }
+        }
+
+        let msg = format!("log begins after {}", timestamp.to_int());
+        Err(ErrorKind::NotFound(msg).into())
+    }
+
+    /// Obtain an entry from the directory
+    pub fn find_entry(&self, path: &Path) -> Result<Entry> {
+        Entry::find(&self.adapter, path)
+    }
+
+    /// List the children of the entry at the given path, along with their classes
+    pub fn list(&self, path: &Path) -> Result<Vec<(PathBuf, Class)>> {
+        let txn = self.adapter.ro_transaction()?;
+        let id = self.adapter.find_direntry(&txn, path)?.id;
+
+        self.adapter
+            .list_children(&txn, id)?
+            .iter()
+            .map(|child| {
+                let mut child_path = path.to_owned();
+                child_path.push(child.name);
+                Ok((child_path, self.adapter.find_entry(&txn, &child.id)?.class))
+            })
+            .collect()
+    }
+
+    /// List every entry beneath the given path depth-first, along with their classes
+    pub fn walk(&self, path: &Path) -> Result<Vec<(PathBuf, Class)>> {
+        let txn = self.adapter.ro_transaction()?;
+
+        self.adapter
+            .walk(&txn, path)?
+            .map(|result| {
+                let (child_path, child) = result?;
+                Ok((child_path, self.adapter.find_entry(&txn, &child.id)?.class))
+            })
+            .collect()
     }
 
     /// Obtain a credential from the directory
     pub fn find_credential(&self, path: &Path) -> Result<Credential> {
-        match Entry::find(&self.0, path)? {
+        match Entry::find(&self.adapter, path)? {
             Entry::Credential(credential_entry) => Ok(credential_entry),
             other => {
                 let msg = format!("expecting credential, found {:?}", other);
@@ -153,45 +680,267 @@ impl Server {
             }
         }
     }
+
+    /// Obtain the metadata of the entry at the given path, e.g. its current version
+    pub fn find_metadata(&self, path: &Path) -> Result<Metadata> {
+        let txn = self.adapter.ro_transaction()?;
+        let id = self.adapter.find_direntry(&txn, path)?.id;
+        self.adapter.find_metadata(&txn, &id)
+    }
+
+    /// Find the path to the signature key pair credential with the given public key
+    pub fn find_signer(&self, public_key: &[u8]) -> Result<PathBuf> {
+        let txn = self.adapter.ro_transaction()?;
+        let credential_id = self.adapter.find_signer(&txn, public_key)?;
+        self.adapter.find_path(&txn, &credential_id)
+    }
+
+    /// List the blocks in the log, newest first, along with the credentials which signed them
+    /// (as of when each block was applied).
+    ///
+    /// If a path is given, only blocks containing ops affecting the entry at that path (or
+    /// anything beneath it) are listed, and only those ops are included. The entry is followed
+    /// back through any moves, so its history from before it was moved is included too.
+    pub fn log(&self, path: Option<&Path>) -> Result<Vec<LogEntry>> {
+        let txn = self.adapter.ro_transaction()?;
+        let current_height = self.adapter.current_block_height(&txn)?;
+        let mut path = path.map(|path| path.to_owned());
+        let mut entries = Vec::new();
+
+        for block in self.adapter.find_blocks(&txn, 0..current_height + 1).rev() {
+            let block = block?;
+            let body = block.get_body();
+            let mut ops = Vec::new();
+
+            // Ops are visited newest first, tracking the path the entry had before each one
+            for op in body.get_ops().iter().rev() {
+                let before = match path {
+                    Some(ref path) => {
+                        if audit::affects(op, path.as_path()) {
+                            ops.push(op.clone());
+                        }
+
+                        audit::path_before(op, path.as_path())
+                    }
+                    None => {
+                        ops.push(op.clone());
+                        None
+                    }
+                };
+
+                if before.is_some() {
+                    path = before;
+                }
+            }
+
+            ops.reverse();
+
+            if path.is_none() || !ops.is_empty() {
+                let block_id = BlockId::of(&block);
+                let signers = block
+                    .get_witness()
+                    .get_signatures()
+                    .iter()
+                    .map(|signature| {
+                        let public_key = signature.get_public_key();
+                        let credential_path =
+                            self.adapter.find_block_signer(&txn, &block_id, public_key);
+
+                        LogSigner {
+                            public_key: Vec::from(public_key),
+                            credential_path: credential_path.ok(),
+                        }
+                    })
+                    .collect();
+
+                entries.push(LogEntry {
+                    block_id: block_id,
+                    timestamp: Timestamp::at(body.get_timestamp()),
+                    signers: signers,
+                    comment: body.get_comment().to_owned(),
+                    ops: ops,
+                });
+            }
+        }
+
+        Ok(entries)
+    }
+
+    /// Begin a transform of the directory which allows this server's maximum clock skew
+    fn transform(&self) -> Result<Transform<A>> {
+        let mut transform = Transform::new(&self.adapter)?;
+        transform.set_max_clock_skew(self.max_clock_skew);
+        Ok(transform)
+    }
+
+    /// Add an object to the directory in a new block signed by the given administrator. In a dry
+    /// run, the block is fully checked but the database is left unchanged
+    fn add_object(
+        &self,
+        admin_keypair: &KeyPair,
+        path: PathBuf,
+        object: Object,
+        comment: &str,
+        dry_run: bool,
+    ) -> Result<()> {
+        let mut op = Op::new();
+        op.set_optype(op::Type::ADD);
+        op.set_path(path.into());
+        op.set_object(object);
+
+        let mut block = self.propose(vec![op], Timestamp::now(), comment)?;
+        admin_keypair.cosign_block(&mut block)?;
+
+        if dry_run {
+            self.dry_run_block(&block)
+        } else {
+            self.apply_block(&block)
+        }
+    }
+
+    /// Replay the given blocks (starting with the initial block) into an in-memory database
+    #[cfg(feature = "memory-adapter")]
+    fn replay<I>(blocks: I) -> Result<Server<MemoryAdapter>>
+    where
+        I: IntoIterator<Item = Result<Block>>,
+    {
+        let adapter = MemoryAdapter::new();
+
+        for block in blocks {
+            let mut transform = Transform::new(&adapter)?;
+            transform.apply(&block?)?;
+            transform.commit()?;
+        }
+
+        Ok(Server::new(adapter))
+    }
+
+    /// Read every block in the log, from the initial block to the current one, ensuring each is
+    /// stored under its own ID and that the chain of parent IDs is unbroken
+    fn read_log(&self) -> Result<Vec<Block>> {
+        let txn = self.adapter.ro_transaction()?;
+        let initial_block_id = self.adapter.initial_block_id(&txn)?;
+        let mut block_id = self.adapter.current_block_id(&txn)?;
+        let mut blocks = Vec::new();
+
+        // Follow parent IDs backwards from the current block rather than trusting the height index
+        loop {
+            let block = self.adapter.find_block(&txn, &block_id)?;
+
+            if BlockId::of(&block) != block_id {
+                let msg = format!(
+                    "block stored under {} has a different ID",
+                    BASE64URL.encode(block_id.as_ref())
+                );
+                return Err(ErrorKind::VerificationFailure(msg).into());
+            }
+
+            let parent_id = BlockId::from_bytes(block.get_body().get_parent_id())?;
+            blocks.push(block);
+
+            if parent_id == BlockId::zero() {
+                if block_id != initial_block_id {
+                    let msg = format!(
+                        "log begins at {}, expected initial block {}",
+                        BASE64URL.encode(block_id.as_ref()),
+                        BASE64URL.encode(initial_block_id.as_ref())
+                    );
+                    return Err(ErrorKind::VerificationFailure(msg).into());
+                }
+
+                break;
+            }
+
+            block_id = parent_id;
+        }
+
+        blocks.reverse();
+        Ok(blocks)
+    }
 }
 
-#[cfg(test)]
+/// Generate a new signature key pair and seal it under a key derived from the given password,
+/// returning the key pair, its sealed form, and the salt used to derive the sealing key
+fn generate_sealed_keypair(
+    rng: &SecureRandom,
+    password: &str,
+) -> Result<(KeyPair, Vec<u8>, Vec<u8>)> {
+    let salt = password::random_salt(rng)?;
+
+    let mut symmetric_key = [0u8; AES256GCM_KEY_SIZE];
+    password::derive(PasswordAlg::SCRYPT, &salt, password, &mut symmetric_key);
+
+    // NOTE: Fixed nonce. Passwords should be randomly generated and never reused
+    let nonce = [0u8; AES256GCM_NONCE_SIZE];
+
+    // TODO: honor ciphersuite algorithms
+    let (keypair, keypair_sealed) = KeyPair::generate_and_seal(
+        SignatureAlg::Ed25519,
+        EncryptionAlg::AES256GCM,
+        rng,
+        &symmetric_key,
+        &nonce,
+    )?;
+
+    Ok((keypair, keypair_sealed, Vec::from(&salt[..])))
+}
+
+#[cfg(all(test, feature = "memory-adapter"))]
 mod tests {
+    use adapter::{Adapter, Transaction};
+    use adapter::memory::MemoryAdapter;
     use alg::{CipherSuite, PasswordAlg};
+    use block::Block;
     use crypto::password;
     use crypto::signing::KeyPair;
     use crypto::symmetric::AES256GCM_KEY_SIZE;
-    use path::PathBuf;
+    use data_encoding::BASE64URL;
+    use entry::{Class, Entry, SerializedEntry};
+    use errors::*;
+    use manifest::{Manifest, State};
+    use object::Object;
+    use object::domain::Domain;
+    use op::{self, Op};
+    use path::{Path, PathBuf};
+    use plan::{Change, FieldChange};
+    use protobuf::{self, Message};
     use ring::rand;
     use server::Server;
-    use server::tempdir::TempDir;
+    use timestamp::Timestamp;
+    use transform::DEFAULT_MAX_CLOCK_SKEW;
 
     const ADMIN_USERNAME: &str = "manager";
     const ADMIN_PASSWORD: &str = "The Magic Words are Squeamish Ossifrage";
     const EXAMPLE_DOMAIN: &str = "example.com";
 
-    fn create_database() -> Server {
+    fn create_database() -> Server<MemoryAdapter> {
         let rng = rand::SystemRandom::new();
-        let dir = TempDir::new("ithos-test").unwrap();
-        Server::create_database(
-            dir.path(),
+        Server::initialize(
+            MemoryAdapter::new(),
             &rng,
             CipherSuite::Ed25519_AES256GCM_SHA256,
             ADMIN_USERNAME,
             ADMIN_PASSWORD,
-        ).unwrap();
-        Server::open_database(dir.path()).unwrap()
+        ).unwrap()
     }
 
-    fn admin_keypair(server: &Server) -> KeyPair {
+    fn admin_keypair_path() -> PathBuf {
         let mut keypair_path = PathBuf::new();
         keypair_path.push("global");
         keypair_path.push("users");
         keypair_path.push(ADMIN_USERNAME);
         keypair_path.push("keys");
         keypair_path.push("signing");
+        keypair_path
+    }
 
-        let credential = server.find_credential(keypair_path.as_ref()).unwrap();
+    fn admin_keypair<A>(server: &Server<A>) -> KeyPair
+    where
+        A: for<'a> Adapter<'a>,
+    {
+        let credential = server
+            .find_credential(admin_keypair_path().as_ref())
+            .unwrap();
 
         let mut admin_symmetric_key = [0u8; AES256GCM_KEY_SIZE];
 
@@ -209,9 +958,665 @@ mod tests {
     fn test_add_domain() {
         let server = create_database();
         let keypair = admin_keypair(&server);
+        let path = format!("/{}", EXAMPLE_DOMAIN);
+
+        // Dry runs check the new entry could be added without adding it
+        server
+            .add_domain(&keypair, EXAMPLE_DOMAIN, None, "Testing 1 2 3", true)
+            .unwrap();
+        assert!(server.find_entry(Path::new(&path).unwrap()).is_err());
+
+        server
+            .add_domain(&keypair, EXAMPLE_DOMAIN, None, "Testing 1 2 3", false)
+            .unwrap();
+        assert!(server.find_entry(Path::new(&path).unwrap()).is_ok());
+
+        assert!(
+            server
+                .add_domain(&keypair, EXAMPLE_DOMAIN, None, "Testing 1 2 3", true)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_memory_adapter() {
+        let rng = rand::SystemRandom::new();
+        let server = Server::initialize(
+            MemoryAdapter::new(),
+            &rng,
+            CipherSuite::Ed25519_AES256GCM_SHA256,
+            ADMIN_USERNAME,
+            ADMIN_PASSWORD,
+        ).unwrap();
+
+        let keypair = admin_keypair(&server);
+
+        server
+            .add_domain(&keypair, EXAMPLE_DOMAIN, None, "Testing 1 2 3", false)
+            .unwrap();
+
+        let path = format!("/{}", EXAMPLE_DOMAIN);
+        assert!(server.find_entry(Path::new(&path).unwrap()).is_ok());
+        assert_eq!(server.verify().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_find_signer() {
+        let server = create_database();
+        let keypair = admin_keypair(&server);
+
+        assert_eq!(
+            server.find_signer(keypair.public_key_bytes()).unwrap(),
+            admin_keypair_path()
+        );
+    }
+
+    #[test]
+    fn test_propose_and_apply_block() {
+        let server = create_database();
+        let keypair = admin_keypair(&server);
+
+        let mut domain_object = Object::new();
+        domain_object.set_domain(Domain::new());
+
+        let mut op = Op::new();
+        op.set_optype(op::Type::ADD);
+        op.set_path(format!("/{}", EXAMPLE_DOMAIN));
+        op.set_object(domain_object);
+
+        let proposal = server
+            .propose(vec![op], Timestamp::now(), "Testing 1 2 3")
+            .unwrap();
+
+        // Unsigned blocks are rejected
+        assert!(server.apply_block(&proposal).is_err());
+
+        // Pass the block along as serialized bytes, as we would between administrators
+        let bytes = proposal.write_to_bytes().unwrap();
+        let mut block = protobuf::parse_from_bytes::<Block>(&bytes).unwrap();
+
+        keypair.cosign_block(&mut block).unwrap();
+        server.apply_block(&block).unwrap();
+    }
+
+    #[test]
+    fn test_verify() {
+        let server = create_database();
+        let keypair = admin_keypair(&server);
+
+        server
+            .add_domain(&keypair, EXAMPLE_DOMAIN, None, "Testing 1 2 3", false)
+            .unwrap();
+
+        assert_eq!(server.verify().unwrap(), 2);
+
+        // Tamper with the domain's metadata without going through the log
+        {
+            let adapter = &server.adapter;
+            let mut txn = adapter.rw_transaction().unwrap();
+            let path = format!("/{}", EXAMPLE_DOMAIN);
+            let id = adapter
+                .find_direntry(&txn, Path::new(&path).unwrap())
+                .unwrap()
+                .id;
+
+            let (class, data) = {
+                let entry = adapter.find_entry(&txn, &id).unwrap();
+                (entry.class, entry.data.to_vec())
+            };
+
+            let mut metadata = adapter.find_metadata(&txn, &id).unwrap();
+            metadata.set_version(42);
+
+            let entry = SerializedEntry {
+                id: id,
+                class: class,
+                data: &data,
+            };
+
+            adapter.update_entry(&mut txn, &entry, &metadata).unwrap();
+            txn.commit().unwrap();
+        }
+
+        match server.verify() {
+            Err(Error(ErrorKind::VerificationFailure(_), _)) => (),
+            other => panic!("expected verification failure, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_export_and_import() {
+        let server = create_database();
+        let keypair = admin_keypair(&server);
+
+        server
+            .add_domain(&keypair, EXAMPLE_DOMAIN, None, "Testing 1 2 3", false)
+            .unwrap();
+
+        let mut exported = Vec::new();
+        assert_eq!(server.export(&mut exported).unwrap(), 2);
+
+        let adapter = MemoryAdapter::new();
+        assert_eq!(Server::import_into(&adapter, &mut &exported[..]).unwrap(), 2);
+
+        let imported = Server::new(adapter);
+        assert_eq!(imported.verify().unwrap(), 2);
+        assert_eq!(imported.list(Path::root()).unwrap(), server.list(Path::root()).unwrap());
+
+        // Logs which are cut short can't be imported
+        let truncated = &exported[..exported.len() - 1];
+        assert!(Server::import_into(&MemoryAdapter::new(), &mut &truncated[..]).is_err());
+
+        // Nor can logs which don't begin with an initial block
+        let mut headless = Vec::new();
+        let blocks = server.read_log().unwrap();
+        blocks[1]
+            .write_length_delimited_to_writer(&mut headless)
+            .unwrap();
+
+        assert!(Server::import_into(&MemoryAdapter::new(), &mut &headless[..]).is_err());
+    }
+
+    #[test]
+    fn test_log() {
+        let server = create_database();
+        let keypair = admin_keypair(&server);
+
+        server
+            .add_domain(&keypair, EXAMPLE_DOMAIN, None, "Adding example.com", false)
+            .unwrap();
+        server
+            .add_domain(&keypair, "other.com", None, "Adding other.com", false)
+            .unwrap();
+
+        let log = server.log(None).unwrap();
+        assert_eq!(log.len(), 3);
+        assert_eq!(log[0].comment, "Adding other.com");
+        assert_eq!(log[1].comment, "Adding example.com");
+        assert_eq!(log[0].signers.len(), 1);
+        assert_eq!(log[0].signers[0].public_key, keypair.public_key_bytes());
+        assert_eq!(
+            log[0].signers[0].credential_path,
+            Some(admin_keypair_path())
+        );
+
+        let path = format!("/{}", EXAMPLE_DOMAIN);
+        let log = server.log(Path::new(&path)).unwrap();
+        assert_eq!(log.len(), 1);
+        assert_eq!(log[0].comment, "Adding example.com");
+        assert_eq!(log[0].ops.len(), 1);
+        assert_eq!(log[0].ops[0].get_path(), path);
+    }
+
+    #[test]
+    fn test_log_after_move() {
+        let server = create_database();
+        let keypair = admin_keypair(&server);
+
+        server
+            .add_domain(&keypair, EXAMPLE_DOMAIN, None, "Adding example.com", false)
+            .unwrap();
+
+        let eng = PathBuf::from(format!("/{}/eng", EXAMPLE_DOMAIN));
+        server
+            .add_org_unit(&keypair, eng.as_path(), None, "Adding eng", false)
+            .unwrap();
+        server
+            .add_domain(&keypair, "other.com", None, "Adding other.com", false)
+            .unwrap();
+
+        let mut op = Op::new();
+        op.set_optype(op::Type::MOVE);
+        op.set_path(eng.as_path().to_string());
+        op.set_destination("/other.com/eng".to_string());
+
+        // Moving an entry to a new path keeps its history from before the move
+        let mut block = server
+            .propose(vec![op], Timestamp::now(), "Spinning off eng")
+            .unwrap();
+        keypair.cosign_block(&mut block).unwrap();
+        server.apply_block(&block).unwrap();
+
+        let log = server.log(Path::new("/other.com/eng")).unwrap();
+        assert_eq!(log.len(), 2);
+        assert_eq!(log[0].comment, "Spinning off eng");
+        assert_eq!(log[1].comment, "Adding eng");
+    }
+
+    #[test]
+    fn test_log_after_key_rotation() {
+        let server = create_database();
+        let keypair = admin_keypair(&server);
+        let rng = rand::SystemRandom::new();
+
+        server
+            .add_domain(&keypair, EXAMPLE_DOMAIN, None, "Adding example.com", false)
+            .unwrap();
+
+        // Replace the admin's signature key with a new one
+        let new_keypair = KeyPair::generate(&rng);
+        let mut credential = server
+            .find_credential(admin_keypair_path().as_path())
+            .unwrap();
+        credential.set_public_key(Vec::from(new_keypair.public_key_bytes()));
+
+        let mut object = Object::new();
+        object.set_credential(credential);
+
+        let mut op = Op::new();
+        op.set_optype(op::Type::UPDATE);
+        op.set_path(admin_keypair_path().into());
+        op.set_object(object);
+
+        let mut block = server
+            .propose(vec![op], Timestamp::now(), "Rotating the admin key")
+            .unwrap();
+        keypair.cosign_block(&mut block).unwrap();
+        server.apply_block(&block).unwrap();
+
+        assert!(server.find_signer(keypair.public_key_bytes()).is_err());
+
+        // Earlier blocks still show the credential which signed them
+        let log = server.log(None).unwrap();
+        assert_eq!(log[1].comment, "Adding example.com");
+        assert_eq!(log[1].signers[0].public_key, keypair.public_key_bytes());
+        assert_eq!(
+            log[1].signers[0].credential_path,
+            Some(admin_keypair_path())
+        );
+    }
+
+    #[test]
+    fn test_point_in_time_reads() {
+        let server = create_database();
+        let keypair = admin_keypair(&server);
+
+        server
+            .add_domain(&keypair, EXAMPLE_DOMAIN, None, "Testing 1 2 3", false)
+            .unwrap();
+
+        let log = server.log(None).unwrap();
+        let (current_block, initial_block) = (&log[0], &log[1]);
+        let path = format!("/{}", EXAMPLE_DOMAIN);
+
+        let before = server.at_block(&initial_block.block_id).unwrap();
+        assert!(before.find_entry(Path::new(&path).unwrap()).is_err());
+        assert!(
+            before
+                .find_credential(admin_keypair_path().as_path())
+                .is_ok()
+        );
+
+        let after = server.at_block(&current_block.block_id).unwrap();
+        assert!(after.find_entry(Path::new(&path).unwrap()).is_ok());
+
+        let at_time = server.at_time(current_block.timestamp).unwrap();
+        assert!(at_time.find_entry(Path::new(&path).unwrap()).is_ok());
+
+        let early = Timestamp::at(initial_block.timestamp.to_int() - 1);
+        assert!(server.at_time(early).is_err());
+    }
+
+    #[test]
+    fn test_list_and_walk() {
+        let server = create_database();
+
+        let global = Path::new("/global").unwrap();
+        let children: Vec<String> = server
+            .list(global)
+            .unwrap()
+            .iter()
+            .map(|&(ref path, _)| path.as_path().to_string())
+            .collect();
+        assert_eq!(children, vec!["/global/users"]);
+
+        let descendants = server.walk(global).unwrap();
+        let paths: Vec<String> = descendants
+            .iter()
+            .map(|&(ref path, _)| path.as_path().to_string())
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                "/global/users",
+                "/global/users/manager",
+                "/global/users/manager/keys",
+                "/global/users/manager/keys/signing",
+            ]
+        );
+        assert_eq!(descendants[3].1, Class::Credential);
+    }
+
+    #[test]
+    fn test_add_entries() {
+        let server = create_database();
+        let keypair = admin_keypair(&server);
+        let rng = rand::SystemRandom::new();
+
+        server
+            .add_domain(&keypair, EXAMPLE_DOMAIN, None, "Testing 1 2 3", false)
+            .unwrap();
+
+        let ou_path = PathBuf::from(format!("/{}/eng", EXAMPLE_DOMAIN));
+        let user_path = PathBuf::from(format!("/{}/eng/bob", EXAMPLE_DOMAIN));
+        let keys_path = PathBuf::from(format!("/{}/eng/bob/keys", EXAMPLE_DOMAIN));
+        let credential_path = PathBuf::from(format!("/{}/eng/bob/keys/signing", EXAMPLE_DOMAIN));
+
+        server
+            .add_org_unit(&keypair, ou_path.as_path(), None, "Adding eng", false)
+            .unwrap();
+        server
+            .add_system_user(
+                &keypair,
+                user_path.as_path(),
+                Some("Build bot".to_owned()),
+                "Adding bob",
+                false,
+            )
+            .unwrap();
+        server
+            .add_org_unit(&keypair, keys_path.as_path(), None, "Adding bob's keys", false)
+            .unwrap();
+        server
+            .add_credential(
+                &keypair,
+                &rng,
+                credential_path.as_path(),
+                ADMIN_PASSWORD,
+                None,
+                "Adding bob's signing key",
+                false,
+            )
+            .unwrap();
+
+        match server.find_entry(user_path.as_path()).unwrap() {
+            Entry::System(system) => {
+                assert_eq!(system.get_username(), "bob");
+                assert_eq!(system.get_description(), "Build bot");
+            }
+            other => panic!("unexpected entry: {:?}", other),
+        }
+
+        // The sealed key pair can be recovered with the password
+        let credential = server.find_credential(credential_path.as_path()).unwrap();
+        let mut symmetric_key = [0u8; AES256GCM_KEY_SIZE];
+        password::derive(
+            PasswordAlg::SCRYPT,
+            &credential.salt,
+            ADMIN_PASSWORD,
+            &mut symmetric_key,
+        );
+
+        let bob_keypair = KeyPair::unseal_from_credential(&credential, &symmetric_key).unwrap();
+        assert_eq!(
+            server.find_signer(bob_keypair.public_key_bytes()).unwrap(),
+            credential_path
+        );
+    }
+
+    #[test]
+    fn test_apply_manifest() {
+        let server = create_database();
+        let keypair = admin_keypair(&server);
+
+        let manifest = Manifest::parse(
+            r#"
+            comment = "Onboarding"
+
+            [[add]]
+            path = "/example.com"
+            class = "domain"
+
+            [[add]]
+            path = "/example.com/eng"
+            class = "org_unit"
+
+            [[add]]
+            path = "/example.com/eng/alice"
+            class = "system"
+
+            [[add]]
+            path = "/example.com/eng/bob"
+            class = "system"
+        "#,
+        ).unwrap();
 
+        server.apply_manifest(&keypair, &manifest, false).unwrap();
+
+        let log = server.log(None).unwrap();
+        assert_eq!(log.len(), 2);
+        assert_eq!(log[0].comment, "Onboarding");
+        assert_eq!(log[0].ops.len(), 4);
+
+        let eng = Path::new("/example.com/eng").unwrap();
+        assert_eq!(server.list(eng).unwrap().len(), 2);
+
+        // Dry runs are fully checked, but don't change anything
+        let manifest = Manifest::parse(
+            r#"
+            comment = "Rehearsal"
+
+            [[add]]
+            path = "/example.com/eng/carol"
+            class = "system"
+        "#,
+        ).unwrap();
+
+        server.apply_manifest(&keypair, &manifest, true).unwrap();
+        assert_eq!(server.list(eng).unwrap().len(), 2);
+        assert_eq!(server.log(None).unwrap().len(), 2);
+
+        // Manifests are applied atomically: a bad op rejects the whole block
+        let manifest = Manifest::parse(
+            r#"
+            comment = "Partially valid"
+
+            [[add]]
+            path = "/example.com/eng/carol"
+            class = "system"
+
+            [[add]]
+            path = "/nonexistent/dave"
+            class = "system"
+        "#,
+        ).unwrap();
+
+        assert!(server.apply_manifest(&keypair, &manifest, false).is_err());
+        assert_eq!(server.list(eng).unwrap().len(), 2);
+
+        // Updating a credential keeps its sealed key and validity window
+        let original = server.find_credential(admin_keypair_path().as_ref()).unwrap();
+
+        let manifest = Manifest::parse(&format!(
+            r#"
+            comment = "Describe the administrator's key"
+
+            [[update]]
+            path = "{}"
+            class = "credential"
+            description = "Administrator's signing key"
+            public_key = "{}"
+        "#,
+            admin_keypair_path().as_path().to_string(),
+            BASE64URL.encode(keypair.public_key_bytes())
+        )).unwrap();
+
+        server.apply_manifest(&keypair, &manifest, false).unwrap();
+
+        let updated = server.find_credential(admin_keypair_path().as_ref()).unwrap();
+        assert_eq!(updated.get_description(), "Administrator's signing key");
+        assert_eq!(updated.get_encrypted_value(), original.get_encrypted_value());
+        assert_eq!(updated.get_salt(), original.get_salt());
+        assert_eq!(updated.get_not_after(), original.get_not_after());
+
+        let keypair = admin_keypair(&server);
         server
-            .add_domain(&keypair, EXAMPLE_DOMAIN, None, "Testing 1 2 3")
+            .add_domain(&keypair, "other.com", None, "Still works", false)
             .unwrap();
     }
+
+    #[test]
+    fn test_max_clock_skew() {
+        let mut server = create_database();
+        let keypair = admin_keypair(&server);
+
+        let mut domain_object = Object::new();
+        domain_object.set_domain(Domain::new());
+
+        let mut op = Op::new();
+        op.set_optype(op::Type::ADD);
+        op.set_path(format!("/{}", EXAMPLE_DOMAIN));
+        op.set_object(domain_object);
+
+        // A block proposed on a machine whose clock runs an hour fast
+        let timestamp = Timestamp::now().extend(DEFAULT_MAX_CLOCK_SKEW + 3600);
+        let mut block = server.propose(vec![op], timestamp, "From the future").unwrap();
+        keypair.cosign_block(&mut block).unwrap();
+
+        match server.apply_block(&block) {
+            Err(Error(ErrorKind::TimestampInvalid(_), _)) => (),
+            other => panic!("expected an invalid timestamp, got {:?}", other),
+        }
+
+        server.set_max_clock_skew(2 * 3600);
+        server.apply_block(&block).unwrap();
+    }
+
+    #[test]
+    fn test_plan() {
+        let server = create_database();
+        let keypair = admin_keypair(&server);
+
+        let manifest = Manifest::parse(
+            r#"
+            comment = "Initial layout"
+
+            [[add]]
+            path = "/example.com"
+            class = "domain"
+
+            [[add]]
+            path = "/example.com/eng"
+            class = "org_unit"
+            description = "Engineering"
+
+            [[add]]
+            path = "/example.com/eng/alice"
+            class = "system"
+
+            [[add]]
+            path = "/example.com/eng/bob"
+            class = "system"
+
+            [[add]]
+            path = "/example.com/ops"
+            class = "org_unit"
+
+            [[add]]
+            path = "/example.com/ops/deploy"
+            class = "system"
+        "#,
+        ).unwrap();
+
+        server.apply_manifest(&keypair, &manifest, false).unwrap();
+
+        let state = State::parse(
+            r#"
+            comment = "Reorganization"
+            root = "/example.com"
+
+            [[entry]]
+            path = "/example.com/eng/carol"
+            class = "system"
+
+            [[entry]]
+            path = "/example.com/eng"
+            class = "org_unit"
+            description = "Product engineering"
+
+            [[entry]]
+            path = "/example.com/eng/alice"
+            class = "system"
+        "#,
+        ).unwrap();
+
+        let plan = server.plan(&state).unwrap();
+        let path = |path: &str| Path::new(path).unwrap().to_owned();
+
+        assert_eq!(
+            plan.changes,
+            vec![
+                Change::Delete {
+                    path: path("/example.com/eng/bob"),
+                    recursive: false,
+                },
+                Change::Delete {
+                    path: path("/example.com/ops"),
+                    recursive: true,
+                },
+                Change::Update {
+                    path: path("/example.com/eng"),
+                    fields: vec![
+                        FieldChange {
+                            name: "description",
+                            old: "Engineering".to_owned(),
+                            new: "Product engineering".to_owned(),
+                        },
+                    ],
+                },
+                Change::Add {
+                    path: path("/example.com/eng/carol"),
+                    class: Class::System,
+                    fields: vec![
+                        ("username", "carol".to_owned()),
+                        ("description", "".to_owned()),
+                    ],
+                },
+            ]
+        );
+
+        // Planning doesn't change anything
+        let eng = Path::new("/example.com/eng").unwrap();
+        assert_eq!(server.list(eng).unwrap().len(), 2);
+
+        let mut block = plan.block;
+        keypair.cosign_block(&mut block).unwrap();
+        server.apply_block(&block).unwrap();
+
+        let children: Vec<String> = server
+            .walk(Path::new("/example.com").unwrap())
+            .unwrap()
+            .iter()
+            .map(|&(ref path, _)| path.as_path().to_string())
+            .collect();
+
+        assert_eq!(
+            children,
+            vec![
+                "/example.com/eng",
+                "/example.com/eng/alice",
+                "/example.com/eng/carol",
+            ]
+        );
+
+        // Once the directory matches, there's nothing left to do
+        assert!(server.plan(&state).unwrap().changes.is_empty());
+
+        // Listing an entry without its parent would delete it along with the parent
+        let state = State::parse(
+            r#"
+            comment = "Missing parent"
+            root = "/example.com"
+
+            [[entry]]
+            path = "/example.com/eng/alice"
+            class = "system"
+        "#,
+        ).unwrap();
+
+        match *server.plan(&state).unwrap_err().kind() {
+            ErrorKind::PathInvalid(_) => (),
+            ref other => panic!("unexpected error kind: {:?}", other),
+        }
+    }
 }
@@ -4,9 +4,10 @@
 //! operations. It creates the following directory hierarchy:
 //!
 //! 1) Root object (ala LDAP root DSE)
-//! 2) Global domain
-//! 3) Root administrator account ("manager")
-//! 4) Signing key for root administrator account
+//! 2) Root policy ("admins") granting the administrator account full control of the tree
+//! 3) Global domain
+//! 4) Root administrator account ("manager")
+//! 5) Signing key for root administrator account
 //!
 //! Once this has been done, all subsequent changes to the log can be authorized by credentials
 //!
@@ -19,6 +20,7 @@ use object::Object;
 use object::credential::{self, Credential};
 use object::domain::Domain;
 use object::org_unit::OrgUnit;
+use object::policy::{Action, Policy};
 use object::root::Root;
 use object::system::System;
 use op::{self, Op};
@@ -65,6 +67,29 @@ pub fn create_log(
     root_op.set_object(root_object);
     ops.push(root_op);
 
+    // Root Policy
+    let mut admin_path = PathBuf::new();
+    admin_path.push("global");
+    admin_path.push("users");
+    admin_path.push(&admin_username);
+
+    let mut root_policy = Policy::new();
+    root_policy.set_description(String::from("Root administrators"));
+    root_policy.set_subjects(RepeatedField::from_vec(vec![admin_path.into()]));
+    root_policy.set_actions(vec![Action::ADD, Action::UPDATE, Action::DELETE]);
+
+    let mut root_policy_object = Object::new();
+    root_policy_object.set_policy(root_policy);
+
+    let mut root_policy_path = path.clone();
+    root_policy_path.push("admins");
+
+    let mut root_policy_op = Op::new();
+    root_policy_op.set_optype(op::Type::ADD);
+    root_policy_op.set_path(root_policy_path.into());
+    root_policy_op.set_object(root_policy_object);
+    ops.push(root_policy_op);
+
     // Global Domain
     path.push("global");
 
@@ -3,23 +3,49 @@
 //! This module should ideally be the one true path all adapter writes happen through,
 //! as all writes to the database are mediated by the log.
 //!
-//! BIG SECURITY WARNING: This module does not perform cryptographic authorization checks.
-//! TODO: It should!
+//! Every signature in a block's witness is verified before any of its ops are applied, and each
+//! signer must hold a signature key pair credential in the directory which is valid at the
+//! block's timestamp. The initial block is self-signed, so its signers are instead authorized
+//! against the credentials it creates.
+//!
+//! Each op must additionally be granted to one of the block's signers by a `Policy` located in
+//! one of the entries above the op's path. Policies apply to the whole subtree of the entry
+//! containing them, so the right to add entries in a subtree confers the right to add policies
+//! there too, and should be treated as administrative access to it. Moving an entry requires the
+//! right to delete it from its current location and the right to add it at its destination.
+//!
+//! Policies with a threshold greater than one are mandatory: every op they cover must be signed
+//! by at least that many distinct credentials located under their subjects, regardless of what
+//! other policies grant. Deleting or moving an entry is also covered by the threshold policies
+//! inside its subtree, so a subtree can't be removed or relocated wholesale to escape them.
+//!
+//! Ops which modify existing entries may also specify the version of the entry they expect to
+//! modify, in which case the block is rejected with `OrderingInvalid` unless the entry is still
+//! at that version, allowing read-modify-write cycles to detect concurrent changes.
+//!
+//! Block timestamps determine which credentials are valid and are recorded in the metadata of
+//! the entries a block touches, so they can't be backdated: a block may not claim to be older
+//! than its parent, nor further in the future than the allowed clock skew.
 //!
 
 use adapter::{Adapter, Transaction};
 use block::Block;
+use crypto::signing;
+use data_encoding::BASE64URL;
 use entry::{Class, Entry, SerializedEntry};
 use errors::*;
 use id::{BlockId, EntryId};
 use metadata::Metadata;
+use object::credential;
+use object::policy::{Action, Policy};
 use op::{self, Op};
 use path::{Path, PathBuf};
-use std::collections::HashMap;
+use std::cmp;
+use std::collections::{HashMap, HashSet};
 use timestamp::Timestamp;
 
-#[cfg(test)]
-extern crate tempdir;
+/// Default number of seconds a block's timestamp may be ahead of our own clock
+pub const DEFAULT_MAX_CLOCK_SKEW: u64 = 300;
 
 /// An uncommitted entry being processed during a transactional transform
 #[derive(Debug, Eq, PartialEq, Copy, Clone)]
@@ -35,6 +61,7 @@ pub struct Transform<'a, A: Adapter<'a> + 'a> {
     txn: A::W,
     next_entry_id: EntryId,
     new_entries: HashMap<PathBuf, TransformEntry>,
+    max_clock_skew: u64,
 }
 
 impl<'a, A: Adapter<'a> + 'a> Transform<'a, A> {
@@ -48,9 +75,15 @@ impl<'a, A: Adapter<'a> + 'a> Transform<'a, A> {
             txn: txn,
             next_entry_id: next_entry_id,
             new_entries: HashMap::new(),
+            max_clock_skew: DEFAULT_MAX_CLOCK_SKEW,
         })
     }
 
+    /// Set the number of seconds block timestamps may be ahead of the current time
+    pub fn set_max_clock_skew(&mut self, seconds: u64) {
+        self.max_clock_skew = seconds;
+    }
+
     /// Obtain the current block ID (i.e. prior to the transaction being committed)
     pub fn block_id(&mut self) -> Result<BlockId> {
         self.adapter.current_block_id(&self.txn)
@@ -58,32 +91,318 @@ impl<'a, A: Adapter<'a> + 'a> Transform<'a, A> {
 
     /// Apply the operations in the given block to the database
     pub fn apply(&mut self, block: &Block) -> Result<()> {
+        // Refuse to process blocks which aren't properly signed
+        signing::verify_block(block)?;
+
+        // The initial block creates the credentials which authorize it
+        let initial_block = block.get_body().parent_id == BlockId::zero().as_ref();
+
+        // Timestamps decide which credentials are valid, so check them before the signers
+        self.check_timestamp(block, initial_block)?;
+
+        let signers = if initial_block {
+            Vec::new()
+        } else {
+            self.authorize_signers(block)?
+        };
+
         // NOTE: This only stores the block in the database. It does not process it
         self.adapter.add_block(&mut self.txn, block)?;
 
+        let signers = if initial_block {
+            self.apply_ops(block, None)?;
+            self.authorize_signers(block)?
+        } else {
+            self.apply_ops(block, Some(&signers[..]))?;
+            signers
+        };
+
+        // Keys may later be rotated or deleted, so record who signed the block for the log
+        self.adapter.add_block_signers(&mut self.txn, block, &signers)
+    }
+
+    /// Fully apply a block exactly as `apply` would, verifying its signatures and authorizing
+    /// its ops, then abort the transaction instead of committing it.
+    ///
+    /// This consumes the transform, so a block which passes can be validated before more
+    /// signatures are gathered for it without any risk of it taking effect.
+    pub fn dry_run(mut self, block: &Block) -> Result<()> {
+        let result = self.apply(block);
+        self.abort();
+        result
+    }
+
+    /// Check that the ops in a (possibly unsigned) block could be applied to the database,
+    /// without checking its signatures or whether its ops are authorized by any policies.
+    ///
+    /// Like `dry_run`, this consumes the transform and aborts the transaction afterward.
+    pub fn check(mut self, block: &Block) -> Result<()> {
+        let result = self.apply_unsigned(block);
+        self.abort();
+        result
+    }
+
+    /// Find the authorizations a block which is still gathering signatures lacks, returning a
+    /// description of each (none means the block is fully authorized).
+    ///
+    /// Whatever signatures the block has so far must be valid, and its signers authorized as
+    /// they would be by `apply`, but ops they aren't yet authorized to perform are applied
+    /// anyway so the rest of the block can be checked. Like `dry_run`, this consumes the
+    /// transform and aborts the transaction afterward.
+    pub fn missing_authorizations(mut self, block: &Block) -> Result<Vec<String>> {
+        let result = self.apply_partially_signed(block);
+        self.abort();
+        result
+    }
+
+    /// Commit the transaction in which the ops contained in a block have been applied
+    pub fn commit(self) -> Result<()> {
+        self.txn.commit()
+    }
+
+    /// Abort the transaction, discarding the ops which have been applied in it
+    pub fn abort(self) {
+        self.txn.abort()
+    }
+
+    /// Apply the ops in a block without regard to its signatures
+    fn apply_unsigned(&mut self, block: &Block) -> Result<()> {
+        let initial_block = block.get_body().parent_id == BlockId::zero().as_ref();
+
+        self.check_timestamp(block, initial_block)?;
+        self.adapter.add_block(&mut self.txn, block)?;
+        self.apply_ops(block, None)
+    }
+
+    /// Apply the ops in a (non-initial) block, collecting the authorizations its signers lack
+    fn apply_partially_signed(&mut self, block: &Block) -> Result<Vec<String>> {
+        if block.get_body().parent_id == BlockId::zero().as_ref() {
+            let msg = "the initial block can't be checked for missing signatures".to_string();
+            return Err(ErrorKind::StructureInvalid(msg).into());
+        }
+
+        if !block.get_witness().get_signatures().is_empty() {
+            signing::verify_block(block)?;
+        }
+
+        self.check_timestamp(block, false)?;
+        let signers = self.authorize_signers(block)?;
+        self.adapter.add_block(&mut self.txn, block)?;
+
+        let block_id = BlockId::of(block);
+        let timestamp = Timestamp::at(block.get_body().get_timestamp());
+        let mut missing = Vec::new();
+
+        for op in block.get_body().get_ops() {
+            match self.authorize_op(op, &signers) {
+                Ok(()) => (),
+                Err(Error(ErrorKind::UnauthorizedSigner(msg), _)) => missing.push(msg),
+                Err(err) => return Err(err),
+            }
+
+            self.apply_op(op, &block_id, timestamp)?;
+        }
+
+        Ok(missing)
+    }
+
+    /// Process the ops in a block, authorizing each against the given signers (if any)
+    fn apply_ops(&mut self, block: &Block, signers: Option<&[PathBuf]>) -> Result<()> {
         let block_id = BlockId::of(block);
-        let ops = &block.get_body().get_ops();
-
-        // Process the operations in the block and apply them to the database
-        for op in ops.iter() {
-            match op.get_optype() {
-                op::Type::ADD => {
-                    self.add(
-                        op,
-                        &block_id,
-                        Timestamp::at(block.get_body().get_timestamp()),
-                    )?
+        let timestamp = Timestamp::at(block.get_body().get_timestamp());
+
+        for op in block.get_body().get_ops() {
+            if let Some(signers) = signers {
+                self.authorize_op(op, signers)?;
+            }
+
+            self.apply_op(op, &block_id, timestamp)?;
+        }
+
+        Ok(())
+    }
+
+    /// Apply a single op from the block with the given ID
+    fn apply_op(&mut self, op: &Op, block_id: &BlockId, timestamp: Timestamp) -> Result<()> {
+        match op.get_optype() {
+            op::Type::ADD => self.add(op, block_id, timestamp),
+            op::Type::DELETE => self.delete(op, block_id, timestamp),
+            op::Type::UPDATE => self.update(op, block_id, timestamp),
+            op::Type::MOVE => self.move_entry(op, block_id, timestamp),
+        }
+    }
+
+    /// Ensure the block's timestamp is neither earlier than the current block's (i.e. its
+    /// parent's) nor further in the future than the maximum clock skew allows
+    fn check_timestamp(&self, block: &Block, initial_block: bool) -> Result<()> {
+        let timestamp = block.get_body().get_timestamp();
+        let latest_allowed = Timestamp::now().extend(self.max_clock_skew);
+
+        if timestamp > latest_allowed.to_int() {
+            let msg = format!(
+                "block timestamp {} is more than {} seconds in the future",
+                timestamp,
+                self.max_clock_skew
+            );
+            return Err(ErrorKind::TimestampInvalid(msg).into());
+        }
+
+        if !initial_block {
+            let current_block_id = self.adapter.current_block_id(&self.txn)?;
+            let parent = self.adapter.find_block(&self.txn, &current_block_id)?;
+            let parent_timestamp = parent.get_body().get_timestamp();
+
+            if timestamp < parent_timestamp {
+                let msg = format!(
+                    "block timestamp {} is earlier than its parent's ({})",
+                    timestamp,
+                    parent_timestamp
+                );
+                return Err(ErrorKind::TimestampInvalid(msg).into());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Ensure every signer of the given block holds a signature key pair credential in the
+    /// directory which is valid at the block's timestamp, returning the paths of the credentials
+    fn authorize_signers(&self, block: &Block) -> Result<Vec<PathBuf>> {
+        let timestamp = block.get_body().get_timestamp();
+        let mut signers = Vec::new();
+
+        for signature in block.get_witness().get_signatures() {
+            let public_key = BASE64URL.encode(&signature.public_key);
+
+            let credential_id = match self.adapter.find_signer(&self.txn, &signature.public_key) {
+                Ok(id) => id,
+                Err(Error(ErrorKind::NotFound(_), _)) => {
+                    let msg = format!("no credential for {}", public_key);
+                    return Err(ErrorKind::UnauthorizedSigner(msg).into());
+                }
+                Err(err) => return Err(err),
+            };
+
+            let credential = match self.adapter
+                .find_entry(&self.txn, &credential_id)?
+                .deserialize()? {
+                Entry::Credential(credential) => credential,
+                other => {
+                    let msg = format!("expecting credential, found {:?}", other);
+                    return Err(ErrorKind::TypeInvalid(msg).into());
                 }
             };
+
+            if credential.credential_type != credential::Type::SIGNATURE_KEY_PAIR {
+                let msg = format!("{} is not a signature key", public_key);
+                return Err(ErrorKind::UnauthorizedSigner(msg).into());
+            }
+
+            if timestamp < credential.not_before || timestamp > credential.not_after {
+                let msg = format!("credential for {} is not valid at {}", public_key, timestamp);
+                return Err(ErrorKind::UnauthorizedSigner(msg).into());
+            }
+
+            signers.push(self.adapter.find_path(&self.txn, &credential_id)?);
+        }
+
+        Ok(signers)
+    }
+
+    /// Ensure the signers are authorized to perform the given op
+    fn authorize_op(&self, op: &Op, signers: &[PathBuf]) -> Result<()> {
+        let path = Path::new(op.get_path()).ok_or_else(|| {
+            ErrorKind::PathInvalid(format!("bad path: {}", op.get_path()))
+        })?;
+
+        match op.get_optype() {
+            op::Type::ADD => self.authorize_action(path, Action::ADD, signers),
+            op::Type::DELETE => {
+                self.authorize_action(path, Action::DELETE, signers)?;
+                self.authorize_subtree(path, Action::DELETE, signers)
+            }
+            op::Type::UPDATE => self.authorize_action(path, Action::UPDATE, signers),
+            op::Type::MOVE => {
+                // Moving an entry removes it from one subtree and adds it to another
+                let destination = Path::new(op.get_destination()).ok_or_else(|| {
+                    ErrorKind::PathInvalid(format!("bad destination: {}", op.get_destination()))
+                })?;
+
+                self.authorize_action(path, Action::DELETE, signers)?;
+                self.authorize_subtree(path, Action::DELETE, signers)?;
+                self.authorize_action(destination, Action::ADD, signers)
+            }
+        }
+    }
+
+    /// Ensure a policy in one of the entries above the given path grants the given action to the
+    /// signers, and that the signatures required by any threshold policies covering it are present
+    fn authorize_action(&self, path: &Path, action: Action, signers: &[PathBuf]) -> Result<()> {
+        let mut granted = false;
+        let mut scope = path.parent();
+
+        while let Some(scope_path) = scope {
+            let scope_id = self.adapter.find_direntry(&self.txn, scope_path)?.id;
+
+            for policy in self.find_policies(&scope_id)? {
+                if !policy.get_actions().contains(&action) {
+                    continue;
+                }
+
+                if policy_signatures(&policy, signers) >= cmp::max(policy.get_threshold(), 1) {
+                    granted = true;
+                } else {
+                    check_threshold(&policy, path, action, signers)?;
+                }
+            }
+
+            scope = scope_path.parent();
+        }
+
+        if !granted {
+            let msg = format!("no policy grants {:?} on {}", action, path.to_string());
+            return Err(ErrorKind::UnauthorizedSigner(msg).into());
         }
 
         Ok(())
+    }
+
+    /// Ensure the signatures required by any threshold policies inside the subtree of the given
+    /// path are present, so the subtree can't be removed or relocated by signers who couldn't
+    /// modify it in place
+    fn authorize_subtree(&self, path: &Path, action: Action, signers: &[PathBuf]) -> Result<()> {
+        let mut scope_ids = vec![self.adapter.find_direntry(&self.txn, path)?.id];
+
+        for result in self.adapter.walk(&self.txn, path)? {
+            scope_ids.push(result?.1.id);
+        }
+
+        for scope_id in &scope_ids {
+            for policy in self.find_policies(scope_id)? {
+                if policy.get_actions().contains(&action) {
+                    check_threshold(&policy, path, action, signers)?;
+                }
+            }
+        }
 
+        Ok(())
     }
 
-    /// Commit the transaction in which the ops contained in a block have been applied
-    pub fn commit(self) -> Result<()> {
-        self.txn.commit()
+    /// Find the policies which apply to the subtree of the entry with the given ID
+    fn find_policies(&self, scope_id: &EntryId) -> Result<Vec<Policy>> {
+        let mut policies = Vec::new();
+
+        for policy_id in self.adapter.find_policies(&self.txn, scope_id)? {
+            match self.adapter.find_entry(&self.txn, &policy_id)?.deserialize()? {
+                Entry::Policy(policy) => policies.push(policy),
+                other => {
+                    let msg = format!("expecting policy, found {:?}", other);
+                    return Err(ErrorKind::TypeInvalid(msg).into());
+                }
+            }
+        }
+
+        Ok(policies)
     }
 
     /// Add a new entry to the directory tree
@@ -131,6 +450,7 @@ impl<'a, A: Adapter<'a> + 'a> Transform<'a, A> {
         metadata.set_updated_id(Vec::from(block_id.as_ref()));
         metadata.set_created_at(timestamp.to_int());
         metadata.set_updated_at(timestamp.to_int());
+        metadata.set_version(1);
 
         let entry = Entry::from_object(&mut op.get_object().clone())
             .ok_or_else(|| {
@@ -166,6 +486,150 @@ impl<'a, A: Adapter<'a> + 'a> Transform<'a, A> {
         Ok(())
     }
 
+    /// Replace the object in an existing entry with a new one of the same class
+    fn update(&mut self, op: &Op, block_id: &BlockId, timestamp: Timestamp) -> Result<()> {
+        let path = Path::new(op.get_path()).ok_or_else(|| {
+            ErrorKind::PathInvalid(format!("bad path: {}", op.get_path()))
+        })?;
+
+        let class = Class::from_object(op.get_object()).ok_or_else(|| {
+            ErrorKind::TypeInvalid(format!("bad object type: {:?}", op.get_object()))
+        })?;
+
+        let existing = self.get_entry(path)?;
+
+        if existing.class != class {
+            let msg = format!(
+                "can't replace {:?} with {:?} at {}",
+                existing.class,
+                class,
+                path.to_string()
+            );
+            return Err(ErrorKind::TypeInvalid(msg).into());
+        }
+
+        let mut metadata = self.adapter.find_metadata(&self.txn, &existing.id)?;
+        check_expectations(op, path, &metadata)?;
+
+        let version = metadata.get_version() + 1;
+
+        metadata.set_updated_id(Vec::from(block_id.as_ref()));
+        metadata.set_updated_at(timestamp.to_int());
+        metadata.set_version(version);
+
+        let entry = Entry::from_object(&mut op.get_object().clone())
+            .ok_or_else(|| {
+                ErrorKind::SerializationFailure("unsupported object type".to_string())
+            })?;
+
+        let entry = SerializedEntry {
+            id: existing.id,
+            class: class,
+            data: &entry.serialize()?,
+        };
+
+        self.adapter.update_entry(&mut self.txn, &entry, &metadata)
+    }
+
+    /// Move an entry (along with its children) to a new path, keeping its entry ID
+    fn move_entry(&mut self, op: &Op, block_id: &BlockId, timestamp: Timestamp) -> Result<()> {
+        let path = Path::new(op.get_path()).ok_or_else(|| {
+            ErrorKind::PathInvalid(format!("bad path: {}", op.get_path()))
+        })?;
+
+        let destination = Path::new(op.get_destination()).ok_or_else(|| {
+            ErrorKind::PathInvalid(format!("bad destination: {}", op.get_destination()))
+        })?;
+
+        let (destination_parent, destination_name) =
+            match (destination.parent(), destination.entry_name()) {
+                (Some(parent), Some(name)) => (parent, name),
+                _ => {
+                    let msg = "can't move an entry to the root".to_string();
+                    return Err(ErrorKind::StructureInvalid(msg).into());
+                }
+            };
+
+        if destination.starts_with(path) {
+            let msg = format!(
+                "can't move {} underneath itself ({})",
+                path.to_string(),
+                destination.to_string()
+            );
+            return Err(ErrorKind::StructureInvalid(msg).into());
+        }
+
+        let entry = self.get_entry(path)?;
+        let parent_entry = self.get_entry(destination_parent)?;
+
+        if !parent_entry.class.allows_child(&entry.class) {
+            let msg = format!(
+                "{:?} does not allow {:?} as child",
+                parent_entry.class,
+                entry.class
+            );
+            return Err(ErrorKind::StructureInvalid(msg).into());
+        }
+
+        let mut metadata = self.adapter.find_metadata(&self.txn, &entry.id)?;
+        check_expectations(op, path, &metadata)?;
+
+        let version = metadata.get_version() + 1;
+
+        metadata.set_updated_id(Vec::from(block_id.as_ref()));
+        metadata.set_updated_at(timestamp.to_int());
+        metadata.set_version(version);
+
+        self.adapter.move_entry(
+            &mut self.txn,
+            entry.id,
+            parent_entry.id,
+            destination_name,
+            &metadata,
+        )?;
+
+        // Re-key any uncommitted entries which were moved along with this one
+        let moved_paths: Vec<PathBuf> = self.new_entries
+            .keys()
+            .filter(|entry_path| entry_path.as_path().starts_with(path))
+            .cloned()
+            .collect();
+
+        for old_path in moved_paths {
+            let new_entry = self.new_entries.remove(&old_path).unwrap();
+            let suffix = &old_path.as_path().to_string()[path.to_string().len()..];
+            let new_path = PathBuf::from(destination.to_string() + suffix);
+            self.new_entries.insert(new_path, new_entry);
+        }
+
+        Ok(())
+    }
+
+    /// Delete an entry (along with its children, if the op is recursive) from the directory tree
+    fn delete(&mut self, op: &Op, block_id: &BlockId, timestamp: Timestamp) -> Result<()> {
+        let path = Path::new(op.get_path()).ok_or_else(|| {
+            ErrorKind::PathInvalid(format!("bad path: {}", op.get_path()))
+        })?;
+
+        let entry = self.get_entry(path)?;
+        check_expectations(op, path, &self.adapter.find_metadata(&self.txn, &entry.id)?)?;
+
+        self.adapter.delete_entry(
+            &mut self.txn,
+            entry.id,
+            block_id,
+            timestamp,
+            op.get_recursive(),
+        )?;
+
+        // Forget any uncommitted entries which were deleted along with this one
+        self.new_entries.retain(
+            |entry_path, _| !entry_path.as_path().starts_with(path),
+        );
+
+        Ok(())
+    }
+
     /// Get an entry, either from the buffer of uncommitted entries, or failing that, by reading
     /// from the current view of the directory tree
     fn get_entry(&self, path: &Path) -> Result<TransformEntry> {
@@ -183,57 +647,189 @@ impl<'a, A: Adapter<'a> + 'a> Transform<'a, A> {
     }
 }
 
-#[cfg(test)]
+/// Ensure an entry is at the version an op expects it to be, if the op specifies one
+fn check_expectations(op: &Op, path: &Path, metadata: &Metadata) -> Result<()> {
+    let expected_version = op.get_expected_version();
+
+    if expected_version != 0 && expected_version != metadata.get_version() {
+        let msg = format!(
+            "expected {} to be at version {}, found {}",
+            path.to_string(),
+            expected_version,
+            metadata.get_version()
+        );
+        return Err(ErrorKind::OrderingInvalid(msg).into());
+    }
+
+    let expected_updated_id = op.get_expected_updated_id();
+
+    if !expected_updated_id.is_empty() && expected_updated_id != metadata.get_updated_id() {
+        let msg = format!(
+            "expected {} to have been last updated by block {}, found {}",
+            path.to_string(),
+            BASE64URL.encode(expected_updated_id),
+            BASE64URL.encode(metadata.get_updated_id())
+        );
+        return Err(ErrorKind::OrderingInvalid(msg).into());
+    }
+
+    Ok(())
+}
+
+/// Ensure enough distinct signers fall under a policy's subjects to meet its threshold (when it
+/// has one greater than one)
+fn check_threshold(
+    policy: &Policy,
+    path: &Path,
+    action: Action,
+    signers: &[PathBuf],
+) -> Result<()> {
+    let required = policy.get_threshold();
+    let signed = policy_signatures(policy, signers);
+
+    if required > 1 && signed < required {
+        let msg = format!(
+            "{:?} on {} requires {} of {:?} to sign (got {})",
+            action,
+            path.to_string(),
+            required,
+            policy.get_subjects(),
+            signed
+        );
+        return Err(ErrorKind::UnauthorizedSigner(msg).into());
+    }
+
+    Ok(())
+}
+
+/// Count the distinct signers (i.e. credential paths) located under any of the given policy's
+/// subjects, so overlapping subjects can't count one signer twice
+fn policy_signatures(policy: &Policy, signers: &[PathBuf]) -> u32 {
+    let subjects: Vec<&Path> = policy
+        .get_subjects()
+        .iter()
+        .filter_map(|subject| Path::new(subject.as_str()))
+        .collect();
+
+    signers
+        .iter()
+        .filter(|signer| {
+            subjects.iter().any(
+                |subject| signer.as_path().starts_with(subject),
+            )
+        })
+        .collect::<HashSet<_>>()
+        .len() as u32
+}
+
+#[cfg(all(test, feature = "memory-adapter"))]
 pub mod tests {
     use adapter::Adapter;
-    use adapter::lmdb::LmdbAdapter;
-    use alg::DigestAlg;
+    use adapter::memory::MemoryAdapter;
+    use alg::{CipherSuite, DigestAlg};
     use block::{Block, Body};
     use crypto::signing::KeyPair;
+    use entry::Entry;
     use errors::*;
     use id::BlockId;
     use object::Object;
+    use object::credential::{self, Credential};
     use object::domain::Domain;
+    use object::org_unit::OrgUnit;
+    use object::policy::{Action, Policy};
     use object::root::Root;
     use op::{self, Op};
+    use path::Path;
     use protobuf::RepeatedField;
     use ring::rand;
+    use setup;
     use timestamp::Timestamp;
-    use transform::Transform;
-    use transform::tempdir::TempDir;
+    use transform::{DEFAULT_MAX_CLOCK_SKEW, Transform};
 
     const COMMENT: &'static str = "The tree of a thousand users begins with a single block";
 
-    fn test_adapter() -> LmdbAdapter {
-        let dir = TempDir::new("ithos-test").unwrap();
-        LmdbAdapter::create_database(dir.path()).unwrap()
+    fn test_adapter() -> MemoryAdapter {
+        MemoryAdapter::new()
+    }
+
+    fn initial_block(keypair: &KeyPair) -> Block {
+        setup::create_log(
+            CipherSuite::Ed25519_AES256GCM_SHA256,
+            setup::tests::ADMIN_USERNAME,
+            keypair,
+            setup::tests::ADMIN_KEYPAIR_SEALED,
+            setup::tests::ADMIN_KEYPAIR_SALT,
+            setup::tests::COMMENT,
+        )
     }
 
-    fn example_block(parent_id: BlockId, ops: Vec<Op>) -> Block {
+    fn example_body(parent_id: BlockId, timestamp: Timestamp, ops: Vec<Op>) -> Body {
         let mut body = Body::new();
         body.set_parent_id(Vec::from(parent_id.as_ref()));
-        body.set_timestamp(Timestamp::at(1_231_006_505).to_int());
+        body.set_timestamp(timestamp.to_int());
         body.set_ops(RepeatedField::from_vec(ops));
         body.set_comment(COMMENT.to_owned());
-
-        let rng = rand::SystemRandom::new();
-        KeyPair::generate(&rng).sign_block(body)
+        body
     }
 
-    #[test]
-    fn root_nesting_constraint() {
-        let adapter = test_adapter();
-        let mut transform = Transform::new(&adapter).unwrap();
+    fn example_block(keypair: &KeyPair, parent_id: BlockId, ops: Vec<Op>) -> Block {
+        keypair.sign_block(example_body(parent_id, Timestamp::now(), ops))
+    }
 
+    fn example_domain_op(path: &str) -> Op {
         let mut domain_object = Object::new();
         domain_object.set_domain(Domain::new());
+        example_op(path, domain_object)
+    }
 
+    fn example_op(path: &str, object: Object) -> Op {
         let mut op = Op::new();
         op.set_optype(op::Type::ADD);
-        op.set_path("/".to_string());
-        op.set_object(domain_object);
+        op.set_path(path.to_string());
+        op.set_object(object);
+        op
+    }
+
+    fn example_credential_op(path: &str, keypair: &KeyPair) -> Op {
+        let now = Timestamp::now();
+
+        let mut credential = Credential::new();
+        credential.set_credential_type(credential::Type::SIGNATURE_KEY_PAIR);
+        credential.set_public_key(Vec::from(keypair.public_key_bytes()));
+        credential.set_not_before(now.to_int());
+        credential.set_not_after(now.extend(3600).to_int());
 
-        let block = example_block(BlockId::zero(), vec![op]);
+        let mut object = Object::new();
+        object.set_credential(credential);
+        example_op(path, object)
+    }
+
+    fn example_policy_op(
+        path: &str,
+        subjects: &[&str],
+        actions: Vec<Action>,
+        threshold: u32,
+    ) -> Op {
+        let mut policy = Policy::new();
+        policy.set_subjects(RepeatedField::from_vec(
+            subjects.iter().map(|subject| subject.to_string()).collect(),
+        ));
+        policy.set_actions(actions);
+        policy.set_threshold(threshold);
+
+        let mut object = Object::new();
+        object.set_policy(policy);
+        example_op(path, object)
+    }
+
+    #[test]
+    fn root_nesting_constraint() {
+        let adapter = test_adapter();
+        let mut transform = Transform::new(&adapter).unwrap();
+
+        let rng = rand::SystemRandom::new();
+        let keypair = KeyPair::generate(&rng);
+        let block = example_block(&keypair, BlockId::zero(), vec![example_domain_op("/")]);
 
         match *transform
             .apply(&block)
@@ -249,31 +845,24 @@ pub mod tests {
         let adapter = test_adapter();
         let mut transform = Transform::new(&adapter).unwrap();
 
-        let mut root1 = Root::new();
-        root1.set_digest_alg(DigestAlg::SHA256);
-
-        let root2 = root1.clone();
-
-        let mut root1_object = Object::new();
-        root1_object.set_root(root1);
-
-        let mut op1 = Op::new();
-        op1.set_optype(op::Type::ADD);
-        op1.set_path("/".to_string());
-        op1.set_object(root1_object);
+        let rng = rand::SystemRandom::new();
+        let keypair = KeyPair::generate(&rng);
 
-        let block1 = example_block(BlockId::zero(), vec![op1]);
+        let block1 = initial_block(&keypair);
         assert!(transform.apply(&block1).is_ok());
 
-        let mut root2_object = Object::new();
-        root2_object.set_root(root2);
+        let mut root = Root::new();
+        root.set_digest_alg(DigestAlg::SHA256);
 
-        let mut op2 = Op::new();
-        op2.set_optype(op::Type::ADD);
-        op2.set_path("/derp".to_string());
-        op2.set_object(root2_object);
+        let mut root_object = Object::new();
+        root_object.set_root(root);
 
-        let block2 = example_block(BlockId::of(&block1), vec![op2]);
+        let mut op = Op::new();
+        op.set_optype(op::Type::ADD);
+        op.set_path("/derp".to_string());
+        op.set_object(root_object);
+
+        let block2 = example_block(&keypair, BlockId::of(&block1), vec![op]);
         match *transform
             .apply(&block2)
             .expect_err("expected a structural error")
@@ -282,4 +871,734 @@ pub mod tests {
             ref other => panic!("unexpected error kind: {:?}", other),
         }
     }
+
+    #[test]
+    fn forged_signature() {
+        let adapter = test_adapter();
+        let mut transform = Transform::new(&adapter).unwrap();
+
+        let rng = rand::SystemRandom::new();
+        let mut block = initial_block(&KeyPair::generate(&rng));
+        block.mut_body().set_comment("Not what was signed".to_owned());
+
+        match *transform
+            .apply(&block)
+            .expect_err("expected a signature error")
+            .kind() {
+            ErrorKind::SignatureInvalid(_) => (),
+            ref other => panic!("unexpected error kind: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn dry_run() {
+        let adapter = test_adapter();
+        let rng = rand::SystemRandom::new();
+        let keypair = KeyPair::generate(&rng);
+
+        let block1 = initial_block(&keypair);
+        let mut transform = Transform::new(&adapter).unwrap();
+        transform.apply(&block1).unwrap();
+        transform.commit().unwrap();
+
+        let block2 = example_block(
+            &keypair,
+            BlockId::of(&block1),
+            vec![example_domain_op("/example.com")],
+        );
+        Transform::new(&adapter).unwrap().dry_run(&block2).unwrap();
+
+        {
+            let txn = adapter.ro_transaction().unwrap();
+            assert_eq!(adapter.current_block_id(&txn).unwrap(), BlockId::of(&block1));
+            assert!(adapter.find_direntry(&txn, Path::new("/example.com").unwrap()).is_err());
+        }
+
+        // Signers are still authorized in a dry run
+        let unauthorized_block = example_block(
+            &KeyPair::generate(&rng),
+            BlockId::of(&block1),
+            vec![example_domain_op("/example.com")],
+        );
+
+        match *Transform::new(&adapter)
+            .unwrap()
+            .dry_run(&unauthorized_block)
+            .expect_err("expected an authorization error")
+            .kind() {
+            ErrorKind::UnauthorizedSigner(_) => (),
+            ref other => panic!("unexpected error kind: {:?}", other),
+        }
+
+        let mut transform = Transform::new(&adapter).unwrap();
+        transform.apply(&block2).unwrap();
+        transform.commit().unwrap();
+    }
+
+    #[test]
+    fn unauthorized_signer() {
+        let adapter = test_adapter();
+        let mut transform = Transform::new(&adapter).unwrap();
+
+        let rng = rand::SystemRandom::new();
+        let block1 = initial_block(&KeyPair::generate(&rng));
+        transform.apply(&block1).unwrap();
+
+        let block2 = example_block(
+            &KeyPair::generate(&rng),
+            BlockId::of(&block1),
+            vec![example_domain_op("/example.com")],
+        );
+
+        match *transform
+            .apply(&block2)
+            .expect_err("expected an authorization error")
+            .kind() {
+            ErrorKind::UnauthorizedSigner(_) => (),
+            ref other => panic!("unexpected error kind: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn expired_credential() {
+        let adapter = test_adapter();
+        let mut transform = Transform::new(&adapter).unwrap();
+
+        let rng = rand::SystemRandom::new();
+        let keypair = KeyPair::generate(&rng);
+
+        let block1 = initial_block(&keypair);
+        transform.apply(&block1).unwrap();
+
+        // The admin credential created by setup::create_log is valid for 10 years
+        let timestamp = Timestamp::now().extend(20 * 365 * 24 * 60 * 60);
+        transform.set_max_clock_skew(30 * 365 * 24 * 60 * 60);
+        let body = example_body(
+            BlockId::of(&block1),
+            timestamp,
+            vec![example_domain_op("/example.com")],
+        );
+        let block2 = keypair.sign_block(body);
+
+        match *transform
+            .apply(&block2)
+            .expect_err("expected an authorization error")
+            .kind() {
+            ErrorKind::UnauthorizedSigner(_) => (),
+            ref other => panic!("unexpected error kind: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn block_timestamps() {
+        let adapter = test_adapter();
+        let mut transform = Transform::new(&adapter).unwrap();
+
+        let rng = rand::SystemRandom::new();
+        let keypair = KeyPair::generate(&rng);
+
+        let block1 = initial_block(&keypair);
+        transform.apply(&block1).unwrap();
+        transform.commit().unwrap();
+
+        let parent_timestamp = block1.get_body().get_timestamp();
+        let backdated = Timestamp::at(parent_timestamp - 60);
+        let postdated = Timestamp::now().extend(DEFAULT_MAX_CLOCK_SKEW + 60);
+
+        for timestamp in &[backdated, postdated] {
+            let mut transform = Transform::new(&adapter).unwrap();
+            let body = example_body(
+                BlockId::of(&block1),
+                *timestamp,
+                vec![example_domain_op("/example.com")],
+            );
+
+            match *transform
+                .apply(&keypair.sign_block(body))
+                .expect_err("expected a timestamp error")
+                .kind() {
+                ErrorKind::TimestampInvalid(_) => (),
+                ref other => panic!("unexpected error kind: {:?}", other),
+            }
+        }
+
+        // Clock skew within the configured allowance is tolerated
+        let mut transform = Transform::new(&adapter).unwrap();
+        transform.set_max_clock_skew(DEFAULT_MAX_CLOCK_SKEW + 3600);
+
+        let body = example_body(
+            BlockId::of(&block1),
+            postdated,
+            vec![example_domain_op("/example.com")],
+        );
+        transform.apply(&keypair.sign_block(body)).unwrap();
+    }
+
+    #[test]
+    fn delegated_policy() {
+        let adapter = test_adapter();
+        let mut transform = Transform::new(&adapter).unwrap();
+
+        let rng = rand::SystemRandom::new();
+        let admin_keypair = KeyPair::generate(&rng);
+        let delegate_keypair = KeyPair::generate(&rng);
+
+        let block1 = initial_block(&admin_keypair);
+        transform.apply(&block1).unwrap();
+
+        let mut keys_object = Object::new();
+        keys_object.set_org_unit(OrgUnit::new());
+
+        let block2 = example_block(
+            &admin_keypair,
+            BlockId::of(&block1),
+            vec![
+                example_domain_op("/example.com"),
+                example_op("/example.com/keys", keys_object),
+                example_credential_op("/example.com/keys/admin", &delegate_keypair),
+                example_policy_op(
+                    "/example.com/admins",
+                    &["/example.com/keys"],
+                    vec![Action::ADD],
+                    0,
+                ),
+            ],
+        );
+        transform.apply(&block2).unwrap();
+
+        let block3 = example_block(
+            &delegate_keypair,
+            BlockId::of(&block2),
+            vec![example_domain_op("/example.com/eng")],
+        );
+        transform.apply(&block3).unwrap();
+
+        let block4 = example_block(
+            &delegate_keypair,
+            BlockId::of(&block3),
+            vec![example_domain_op("/other.com")],
+        );
+
+        match *transform
+            .apply(&block4)
+            .expect_err("expected an authorization error")
+            .kind() {
+            ErrorKind::UnauthorizedSigner(_) => (),
+            ref other => panic!("unexpected error kind: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn threshold_policy() {
+        let adapter = test_adapter();
+        let mut transform = Transform::new(&adapter).unwrap();
+
+        let rng = rand::SystemRandom::new();
+        let admin_keypair = KeyPair::generate(&rng);
+        let keypairs: Vec<KeyPair> = (0..3).map(|_| KeyPair::generate(&rng)).collect();
+
+        let block1 = initial_block(&admin_keypair);
+        transform.apply(&block1).unwrap();
+
+        let mut keys_object = Object::new();
+        keys_object.set_org_unit(OrgUnit::new());
+
+        let block2 = example_block(
+            &admin_keypair,
+            BlockId::of(&block1),
+            vec![
+                example_domain_op("/example.com"),
+                example_op("/example.com/keys", keys_object),
+                example_credential_op("/example.com/keys/a", &keypairs[0]),
+                example_credential_op("/example.com/keys/b", &keypairs[1]),
+                example_credential_op("/example.com/keys/c", &keypairs[2]),
+                example_policy_op(
+                    "/example.com/admins",
+                    &["/example.com/keys/a", "/example.com/keys/b", "/example.com/keys/c"],
+                    vec![Action::ADD],
+                    2,
+                ),
+            ],
+        );
+        transform.apply(&block2).unwrap();
+        transform.commit().unwrap();
+
+        // Neither the root administrator nor a single subject can satisfy the threshold alone
+        for keypair in &[&admin_keypair, &keypairs[0]] {
+            let mut transform = Transform::new(&adapter).unwrap();
+            let block = example_block(
+                keypair,
+                BlockId::of(&block2),
+                vec![example_domain_op("/example.com/eng")],
+            );
+
+            match *transform
+                .apply(&block)
+                .expect_err("expected an authorization error")
+                .kind() {
+                ErrorKind::UnauthorizedSigner(_) => (),
+                ref other => panic!("unexpected error kind: {:?}", other),
+            }
+        }
+
+        let mut block3 = example_block(
+            &keypairs[0],
+            BlockId::of(&block2),
+            vec![example_domain_op("/example.com/eng")],
+        );
+
+        // Blocks still gathering signatures report the authorizations they lack
+        let missing = Transform::new(&adapter)
+            .unwrap()
+            .missing_authorizations(&block3)
+            .unwrap();
+        assert_eq!(missing.len(), 1);
+
+        keypairs[2].cosign_block(&mut block3).unwrap();
+
+        let missing = Transform::new(&adapter)
+            .unwrap()
+            .missing_authorizations(&block3)
+            .unwrap();
+        assert!(missing.is_empty());
+
+        let mut transform = Transform::new(&adapter).unwrap();
+        transform.apply(&block3).unwrap();
+    }
+
+    #[test]
+    fn threshold_subjects() {
+        // Overlapping subjects, and a single subject containing several credentials
+        let subject_lists: [&[&str]; 2] = [
+            &["/example.com/keys", "/example.com/keys/a"],
+            &["/example.com/keys"],
+        ];
+
+        for subjects in &subject_lists {
+            let adapter = test_adapter();
+            let mut transform = Transform::new(&adapter).unwrap();
+
+            let rng = rand::SystemRandom::new();
+            let admin_keypair = KeyPair::generate(&rng);
+            let keypairs: Vec<KeyPair> = (0..2).map(|_| KeyPair::generate(&rng)).collect();
+
+            let block1 = initial_block(&admin_keypair);
+            transform.apply(&block1).unwrap();
+
+            let mut keys_object = Object::new();
+            keys_object.set_org_unit(OrgUnit::new());
+
+            let block2 = example_block(
+                &admin_keypair,
+                BlockId::of(&block1),
+                vec![
+                    example_domain_op("/example.com"),
+                    example_op("/example.com/keys", keys_object),
+                    example_credential_op("/example.com/keys/a", &keypairs[0]),
+                    example_credential_op("/example.com/keys/b", &keypairs[1]),
+                    example_policy_op("/example.com/admins", subjects, vec![Action::ADD], 2),
+                ],
+            );
+            transform.apply(&block2).unwrap();
+            transform.commit().unwrap();
+
+            // Thresholds count distinct signers, however many subjects each one falls under
+            {
+                let mut transform = Transform::new(&adapter).unwrap();
+                let block = example_block(
+                    &keypairs[0],
+                    BlockId::of(&block2),
+                    vec![example_domain_op("/example.com/eng")],
+                );
+
+                match *transform
+                    .apply(&block)
+                    .expect_err("expected an authorization error")
+                    .kind() {
+                    ErrorKind::UnauthorizedSigner(_) => (),
+                    ref other => panic!("unexpected error kind: {:?}", other),
+                }
+            }
+
+            let mut block3 = example_block(
+                &keypairs[0],
+                BlockId::of(&block2),
+                vec![example_domain_op("/example.com/eng")],
+            );
+            keypairs[1].cosign_block(&mut block3).unwrap();
+
+            let mut transform = Transform::new(&adapter).unwrap();
+            transform.apply(&block3).unwrap();
+        }
+    }
+
+    #[test]
+    fn threshold_subtree() {
+        let adapter = test_adapter();
+        let mut transform = Transform::new(&adapter).unwrap();
+
+        let rng = rand::SystemRandom::new();
+        let admin_keypair = KeyPair::generate(&rng);
+        let keypairs: Vec<KeyPair> = (0..3).map(|_| KeyPair::generate(&rng)).collect();
+
+        let block1 = initial_block(&admin_keypair);
+        transform.apply(&block1).unwrap();
+
+        let mut keys_object = Object::new();
+        keys_object.set_org_unit(OrgUnit::new());
+
+        let block2 = example_block(
+            &admin_keypair,
+            BlockId::of(&block1),
+            vec![
+                example_domain_op("/example.com"),
+                example_op("/example.com/keys", keys_object),
+                example_credential_op("/example.com/keys/a", &keypairs[0]),
+                example_credential_op("/example.com/keys/b", &keypairs[1]),
+                example_credential_op("/example.com/keys/c", &keypairs[2]),
+                example_policy_op(
+                    "/example.com/admins",
+                    &["/example.com/keys"],
+                    vec![Action::ADD, Action::DELETE],
+                    2,
+                ),
+                example_domain_op("/other.com"),
+            ],
+        );
+        transform.apply(&block2).unwrap();
+        transform.commit().unwrap();
+
+        let mut delete_op = Op::new();
+        delete_op.set_optype(op::Type::DELETE);
+        delete_op.set_path("/example.com".to_string());
+        delete_op.set_recursive(true);
+
+        let mut move_op = Op::new();
+        move_op.set_optype(op::Type::MOVE);
+        move_op.set_path("/example.com".to_string());
+        move_op.set_destination("/other.com/example.com".to_string());
+
+        // The root administrator can't remove or relocate the subtree the policy protects
+        for op in &[&delete_op, &move_op] {
+            let mut transform = Transform::new(&adapter).unwrap();
+            let block = example_block(&admin_keypair, BlockId::of(&block2), vec![(*op).clone()]);
+
+            match *transform
+                .apply(&block)
+                .expect_err("expected an authorization error")
+                .kind() {
+                ErrorKind::UnauthorizedSigner(_) => (),
+                ref other => panic!("unexpected error kind: {:?}", other),
+            }
+        }
+
+        let mut block3 = example_block(&admin_keypair, BlockId::of(&block2), vec![delete_op]);
+        keypairs[0].cosign_block(&mut block3).unwrap();
+        keypairs[1].cosign_block(&mut block3).unwrap();
+
+        let mut transform = Transform::new(&adapter).unwrap();
+        transform.apply(&block3).unwrap();
+    }
+
+    #[test]
+    fn delete_entries() {
+        let adapter = test_adapter();
+
+        let rng = rand::SystemRandom::new();
+        let keypair = KeyPair::generate(&rng);
+
+        let block1 = initial_block(&keypair);
+        let block2 = example_block(
+            &keypair,
+            BlockId::of(&block1),
+            vec![
+                example_domain_op("/example.com"),
+                example_domain_op("/example.com/eng"),
+            ],
+        );
+
+        let mut transform = Transform::new(&adapter).unwrap();
+        transform.apply(&block1).unwrap();
+        transform.apply(&block2).unwrap();
+        transform.commit().unwrap();
+
+        let mut delete_op = Op::new();
+        delete_op.set_optype(op::Type::DELETE);
+        delete_op.set_path("/example.com".to_string());
+
+        {
+            let mut transform = Transform::new(&adapter).unwrap();
+            let block = example_block(&keypair, BlockId::of(&block2), vec![delete_op.clone()]);
+
+            match *transform
+                .apply(&block)
+                .expect_err("expected a structural error")
+                .kind() {
+                ErrorKind::StructureInvalid(_) => (),
+                ref other => panic!("unexpected error kind: {:?}", other),
+            }
+        }
+
+        delete_op.set_recursive(true);
+        let block3 = example_block(&keypair, BlockId::of(&block2), vec![delete_op]);
+
+        let entry_id = {
+            let txn = adapter.ro_transaction().unwrap();
+            let path = Path::new("/example.com/eng").unwrap();
+            adapter.find_direntry(&txn, path).unwrap().id
+        };
+
+        let mut transform = Transform::new(&adapter).unwrap();
+        transform.apply(&block3).unwrap();
+        transform.commit().unwrap();
+
+        let txn = adapter.ro_transaction().unwrap();
+        let path = Path::new("/example.com/eng").unwrap();
+        assert!(adapter.find_direntry(&txn, path).is_err());
+        assert!(adapter.find_entry(&txn, &entry_id).is_err());
+
+        let metadata = adapter.find_metadata(&txn, &entry_id).unwrap();
+        assert_eq!(metadata.get_deleted_id(), BlockId::of(&block3).as_ref());
+    }
+
+    #[test]
+    fn update_entries() {
+        let adapter = test_adapter();
+
+        let rng = rand::SystemRandom::new();
+        let keypair = KeyPair::generate(&rng);
+
+        let block1 = initial_block(&keypair);
+        let block2 = example_block(
+            &keypair,
+            BlockId::of(&block1),
+            vec![example_domain_op("/example.com")],
+        );
+
+        let mut domain = Domain::new();
+        domain.set_description("Updated description".to_string());
+
+        let mut domain_object = Object::new();
+        domain_object.set_domain(domain);
+
+        let mut update_op = example_op("/example.com", domain_object);
+        update_op.set_optype(op::Type::UPDATE);
+
+        let block3 = example_block(&keypair, BlockId::of(&block2), vec![update_op]);
+
+        let mut transform = Transform::new(&adapter).unwrap();
+        transform.apply(&block1).unwrap();
+        transform.apply(&block2).unwrap();
+        transform.apply(&block3).unwrap();
+        transform.commit().unwrap();
+
+        {
+            let txn = adapter.ro_transaction().unwrap();
+            let path = Path::new("/example.com").unwrap();
+            let id = adapter.find_direntry(&txn, path).unwrap().id;
+
+            let metadata = adapter.find_metadata(&txn, &id).unwrap();
+            assert_eq!(metadata.get_version(), 2);
+            assert_eq!(metadata.get_created_id(), BlockId::of(&block2).as_ref());
+            assert_eq!(metadata.get_updated_id(), BlockId::of(&block3).as_ref());
+
+            match adapter.find_entry(&txn, &id).unwrap().deserialize().unwrap() {
+                Entry::Domain(domain) => assert_eq!(domain.description, "Updated description"),
+                other => panic!("unexpected entry: {:?}", other),
+            }
+        }
+
+        let mut org_unit_object = Object::new();
+        org_unit_object.set_org_unit(OrgUnit::new());
+
+        let mut update_op = example_op("/example.com", org_unit_object);
+        update_op.set_optype(op::Type::UPDATE);
+
+        let block4 = example_block(&keypair, BlockId::of(&block3), vec![update_op]);
+
+        let mut transform = Transform::new(&adapter).unwrap();
+        match *transform
+            .apply(&block4)
+            .expect_err("expected a type error")
+            .kind() {
+            ErrorKind::TypeInvalid(_) => (),
+            ref other => panic!("unexpected error kind: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn rotate_signing_key() {
+        let adapter = test_adapter();
+
+        let rng = rand::SystemRandom::new();
+        let old_keypair = KeyPair::generate(&rng);
+        let new_keypair = KeyPair::generate(&rng);
+
+        let credential_path = "/global/users/manager/keys/signing";
+        let mut rotate_op = example_credential_op(credential_path, &new_keypair);
+        rotate_op.set_optype(op::Type::UPDATE);
+
+        let block1 = initial_block(&old_keypair);
+        let block2 = example_block(&old_keypair, BlockId::of(&block1), vec![rotate_op]);
+
+        let mut transform = Transform::new(&adapter).unwrap();
+        transform.apply(&block1).unwrap();
+        transform.apply(&block2).unwrap();
+        transform.commit().unwrap();
+
+        {
+            let mut transform = Transform::new(&adapter).unwrap();
+            let block = example_block(
+                &old_keypair,
+                BlockId::of(&block2),
+                vec![example_domain_op("/example.com")],
+            );
+
+            match *transform
+                .apply(&block)
+                .expect_err("expected an authorization error")
+                .kind() {
+                ErrorKind::UnauthorizedSigner(_) => (),
+                ref other => panic!("unexpected error kind: {:?}", other),
+            }
+        }
+
+        let block3 = example_block(
+            &new_keypair,
+            BlockId::of(&block2),
+            vec![example_domain_op("/example.com")],
+        );
+
+        let mut transform = Transform::new(&adapter).unwrap();
+        transform.apply(&block3).unwrap();
+    }
+
+    #[test]
+    fn expected_versions() {
+        let adapter = test_adapter();
+
+        let rng = rand::SystemRandom::new();
+        let keypair = KeyPair::generate(&rng);
+
+        let block1 = initial_block(&keypair);
+        let block2 = example_block(
+            &keypair,
+            BlockId::of(&block1),
+            vec![example_domain_op("/example.com")],
+        );
+
+        let mut update_op = example_domain_op("/example.com");
+        update_op.set_optype(op::Type::UPDATE);
+        update_op.set_expected_version(1);
+        update_op.set_expected_updated_id(Vec::from(BlockId::of(&block2).as_ref()));
+
+        let block3 = example_block(&keypair, BlockId::of(&block2), vec![update_op.clone()]);
+
+        let mut transform = Transform::new(&adapter).unwrap();
+        transform.apply(&block1).unwrap();
+        transform.apply(&block2).unwrap();
+        transform.apply(&block3).unwrap();
+        transform.commit().unwrap();
+
+        // Replaying the same read-modify-write is rejected as the entry has since changed
+        let mut stale_version_op = update_op.clone();
+        stale_version_op.clear_expected_updated_id();
+
+        let mut stale_updated_id_op = example_domain_op("/example.com");
+        stale_updated_id_op.set_optype(op::Type::DELETE);
+        stale_updated_id_op.set_expected_updated_id(Vec::from(BlockId::of(&block2).as_ref()));
+
+        for op in vec![update_op, stale_version_op, stale_updated_id_op] {
+            let mut transform = Transform::new(&adapter).unwrap();
+            let block = example_block(&keypair, BlockId::of(&block3), vec![op]);
+
+            match *transform
+                .apply(&block)
+                .expect_err("expected an ordering error")
+                .kind() {
+                ErrorKind::OrderingInvalid(_) => (),
+                ref other => panic!("unexpected error kind: {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn move_entries() {
+        let adapter = test_adapter();
+
+        let rng = rand::SystemRandom::new();
+        let keypair = KeyPair::generate(&rng);
+
+        let block1 = initial_block(&keypair);
+        let block2 = example_block(
+            &keypair,
+            BlockId::of(&block1),
+            vec![
+                example_domain_op("/example.com"),
+                example_domain_op("/example.com/eng"),
+                example_domain_op("/example.com/eng/team"),
+                example_domain_op("/other.com"),
+            ],
+        );
+
+        let mut move_op = Op::new();
+        move_op.set_optype(op::Type::MOVE);
+        move_op.set_path("/example.com/eng".to_string());
+        move_op.set_destination("/other.com/engineering".to_string());
+
+        let block3 = example_block(&keypair, BlockId::of(&block2), vec![move_op]);
+
+        let mut transform = Transform::new(&adapter).unwrap();
+        transform.apply(&block1).unwrap();
+        transform.apply(&block2).unwrap();
+
+        let (entry_id, child_id) = {
+            let entry_path = Path::new("/example.com/eng").unwrap();
+            let child_path = Path::new("/example.com/eng/team").unwrap();
+
+            (
+                adapter.find_direntry(&transform.txn, entry_path).unwrap().id,
+                adapter.find_direntry(&transform.txn, child_path).unwrap().id,
+            )
+        };
+
+        transform.apply(&block3).unwrap();
+        transform.commit().unwrap();
+
+        {
+            let txn = adapter.ro_transaction().unwrap();
+
+            let old_path = Path::new("/example.com/eng").unwrap();
+            assert!(adapter.find_direntry(&txn, old_path).is_err());
+
+            let new_path = Path::new("/other.com/engineering").unwrap();
+            assert_eq!(adapter.find_direntry(&txn, new_path).unwrap().id, entry_id);
+
+            let child_path = Path::new("/other.com/engineering/team").unwrap();
+            assert_eq!(adapter.find_direntry(&txn, child_path).unwrap().id, child_id);
+
+            let metadata = adapter.find_metadata(&txn, &entry_id).unwrap();
+            assert_eq!(metadata.get_created_id(), BlockId::of(&block2).as_ref());
+            assert_eq!(metadata.get_updated_id(), BlockId::of(&block3).as_ref());
+        }
+
+        // Entries can't be moved underneath themselves, or to places their class isn't allowed
+        for destination in &["/other.com/engineering/team/other.com", "/global/users/other.com"] {
+            let mut move_op = Op::new();
+            move_op.set_optype(op::Type::MOVE);
+            move_op.set_path("/other.com".to_string());
+            move_op.set_destination(destination.to_string());
+
+            let mut transform = Transform::new(&adapter).unwrap();
+            let block = example_block(&keypair, BlockId::of(&block3), vec![move_op]);
+
+            match *transform
+                .apply(&block)
+                .expect_err("expected a structural error")
+                .kind() {
+                ErrorKind::StructureInvalid(_) => (),
+                ref other => panic!("unexpected error kind: {:?}", other),
+            }
+        }
+    }
 }